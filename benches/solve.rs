@@ -1,5 +1,6 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use std::str::FromStr;
+use sudoku_solver::variants::BoxSudoku;
 use sudoku_solver::{StandardSudoku, Sudoku};
 
 fn solve_easy(c: &mut Criterion) {
@@ -47,5 +48,24 @@ fn solve_evil(c: &mut Criterion) {
     evil.finish();
 }
 
-criterion_group!(benches, solve_easy, solve_medium, solve_hard, solve_evil);
+fn solve_sixteen(c: &mut Criterion) {
+    let sudoku = BoxSudoku::<4, 4, 256>::from_str(
+        " G     4  D63  E 9       G  8B    D 5 A7 C E2  4  F 8BD 2 14 G  G7     2    EF  FD  E7  G      B 3E C    A 5   2     16 F    5   5 G 28 D7   6CF     FC   4       8B   G 2  A1  9F2   E     7  3  956  D  C     B 3F      92C 7  6 2G      A1   E    59  4  6   ",
+    )
+    .unwrap();
+
+    let mut sixteen = c.benchmark_group("16x16");
+    sixteen.sample_size(10);
+    sixteen.bench_function("1", |b| b.iter(|| sudoku.clone().solve()));
+    sixteen.finish();
+}
+
+criterion_group!(
+    benches,
+    solve_easy,
+    solve_medium,
+    solve_hard,
+    solve_evil,
+    solve_sixteen
+);
 criterion_main!(benches);
@@ -0,0 +1,517 @@
+use super::{Candidates, Constraint};
+use crate::error::SudokuError;
+use crate::sudoku::Cell;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// The border between a cell and one of the cells next to it.
+///
+/// Every border is named after the cell above it or to its left, so that each
+/// one has a single name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Border {
+    /// The border on the right side of the cell.
+    Right(usize),
+    /// The border on the bottom side of the cell.
+    Below(usize),
+}
+
+impl Border {
+    /// Get every border of a grid of `size` rows and columns, from top to
+    /// bottom, with the borders between the cells of a row before the ones
+    /// below it.
+    pub fn all(size: usize) -> impl Iterator<Item = Self> {
+        (0..size).flat_map(move |row| {
+            let right = (0..size - 1).map(move |col| Self::Right(row * size + col));
+            let below = (0..size)
+                .filter(move |_| row + 1 < size)
+                .map(move |col| Self::Below(row * size + col));
+            right.chain(below)
+        })
+    }
+
+    /// Get the cells on either side of the border, on a grid of `size` rows
+    /// and columns, or `None` if the border is on the edge of the grid or
+    /// outside of it.
+    pub fn cells(self, size: usize) -> Option<(usize, usize)> {
+        match self {
+            Self::Right(i) if i < size * size && i % size + 1 < size => Some((i, i + 1)),
+            Self::Below(i) if i + size < size * size => Some((i, i + size)),
+            _ => None,
+        }
+    }
+}
+
+/// A dot on the border between two cells, which ties their digits together.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dot {
+    /// The digits are one apart.
+    White,
+    /// One of the digits is double the other.
+    Black,
+}
+
+impl Dot {
+    /// Check if the dot allows digits `a` and `b` on either side of it.
+    pub fn allows(self, a: u8, b: u8) -> bool {
+        match self {
+            Self::White => a.abs_diff(b) == 1,
+            Self::Black => a == 2 * b || b == 2 * a,
+        }
+    }
+}
+
+/// Find the pairs of set cells whose digits `allows` rules out.
+fn check(
+    pairs: impl Iterator<Item = (usize, usize)>,
+    cells: &[Cell],
+    allows: impl Fn(usize, u8, u8) -> bool,
+) -> Vec<(usize, usize)> {
+    pairs
+        .enumerate()
+        .filter(|&(n, (a, b))| match (cells[a], cells[b]) {
+            (Some(x), Some(y)) => !allows(n, x, y),
+            _ => false,
+        })
+        .map(|(_, pair)| pair)
+        .collect()
+}
+
+/// Keep only the candidates of each pair of cells that `allows` can match
+/// with one of the candidates of the other cell.
+fn propagate(
+    pairs: impl Iterator<Item = (usize, usize)>,
+    candidates: &mut [Candidates],
+    allows: impl Fn(usize, u8, u8) -> bool,
+) {
+    for (n, (a, b)) in pairs.enumerate() {
+        let (x, y) = (candidates[a], candidates[b]);
+        candidates[a] =
+            Candidates::from_digits(x.iter().filter(|&d| y.iter().any(|e| allows(n, d, e))));
+        candidates[b] =
+            Candidates::from_digits(y.iter().filter(|&e| x.iter().any(|d| allows(n, d, e))));
+    }
+}
+
+/// Cells next to each other can't have consecutive digits.
+///
+/// ```
+/// use sudoku_solver::constraint::{Constrained, NonConsecutive};
+/// use sudoku_solver::{StandardSudoku, Sudoku};
+///
+/// let mut game = Constrained::new(StandardSudoku::new()).with(NonConsecutive);
+/// game.set(0, Some(4));
+/// game.set(9, Some(5));
+/// assert_eq!(game.conflicts()[0].cells, (0, 9));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NonConsecutive;
+
+impl NonConsecutive {
+    /// Get the cells on either side of every border of a grid with `cells`
+    /// cells.
+    fn pairs(cells: usize) -> impl Iterator<Item = (usize, usize)> {
+        let size = cells.isqrt();
+        Border::all(size).filter_map(move |border| border.cells(size))
+    }
+}
+
+impl Constraint for NonConsecutive {
+    fn explain(&self) -> String {
+        "cells next to each other can't have consecutive digits".to_string()
+    }
+
+    fn check(&self, cells: &[Cell]) -> Vec<(usize, usize)> {
+        check(Self::pairs(cells.len()), cells, |_, a, b| {
+            a.abs_diff(b) != 1
+        })
+    }
+
+    fn propagate(&self, cells: &[Cell], candidates: &mut [Candidates]) {
+        propagate(Self::pairs(cells.len()), candidates, |_, a, b| {
+            a.abs_diff(b) != 1
+        })
+    }
+}
+
+/// Dots on some of the borders between cells, where the digits on either side
+/// of a white dot are one apart, and one of the digits on either side of a
+/// black dot is double the other.
+///
+/// The borders without a dot don't rule anything out, so two cells can be one
+/// apart without a white dot between them.
+///
+/// The dots are written with a line for the borders between the cells of each
+/// row, and a line for the borders below it, using `o` for a white dot, `x`
+/// for a black dot and `.` for a border without one. The size of the grid is
+/// taken from the number of borders, and the line breaks can be left out.
+///
+/// ```
+/// use std::str::FromStr;
+/// use sudoku_solver::constraint::{Border, Constrained, Dot, Kropki};
+/// use sudoku_solver::variants::MiniSudoku;
+/// use sudoku_solver::Sudoku;
+///
+/// let dots = "
+/// o.x..
+/// ......
+/// .....
+/// ......
+/// .....
+/// ......
+/// .....
+/// ......
+/// .....
+/// ......
+/// .....";
+/// let dots = Kropki::from_str(dots).unwrap();
+/// assert_eq!(dots.dots()[1], (Border::Right(2), Dot::Black));
+///
+/// let mut game = Constrained::new(MiniSudoku::new()).with(dots);
+/// game.set(0, Some(1));
+/// game.set(1, Some(3));
+/// assert_eq!(game.conflicts()[0].cells, (0, 1));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Kropki {
+    size: usize,
+    dots: Vec<(Border, Dot)>,
+}
+
+impl Kropki {
+    /// Place `dots` on the borders of a grid of `size` rows and columns.
+    ///
+    /// Every dot must be on a border between two cells of the grid, with at
+    /// most one dot on each border, and the dots can only be added to a grid
+    /// of that size.
+    pub fn new(size: usize, dots: Vec<(Border, Dot)>) -> Result<Self, SudokuError> {
+        for (n, &(border, _)) in dots.iter().enumerate() {
+            if border.cells(size).is_none() || dots[..n].iter().any(|&(b, _)| b == border) {
+                return Err(SudokuError::InvalidDot { dot: n });
+            }
+        }
+
+        Ok(Self { size, dots })
+    }
+
+    /// Get the dots along with the border they are on, in the order they were
+    /// placed.
+    pub fn dots(&self) -> &[(Border, Dot)] {
+        &self.dots
+    }
+
+    /// Get the cells on either side of every dot.
+    fn pairs(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.dots
+            .iter()
+            .filter_map(|(border, _)| border.cells(self.size))
+    }
+
+    /// Check if the dot `n` allows digits `a` and `b` on either side of it.
+    fn allows(&self, n: usize, a: u8, b: u8) -> bool {
+        self.dots[n].1.allows(a, b)
+    }
+}
+
+impl Constraint for Kropki {
+    fn explain(&self) -> String {
+        "cells joined by a white dot must be one apart, and cells joined by a black dot must have one digit double the other"
+            .to_string()
+    }
+
+    fn size(&self) -> Option<usize> {
+        Some(self.size)
+    }
+
+    fn check(&self, cells: &[Cell]) -> Vec<(usize, usize)> {
+        check(self.pairs(), cells, |n, a, b| self.allows(n, a, b))
+    }
+
+    fn propagate(&self, _cells: &[Cell], candidates: &mut [Candidates]) {
+        propagate(self.pairs(), candidates, |n, a, b| self.allows(n, a, b))
+    }
+}
+
+impl Display for Kropki {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (n, border) in Border::all(self.size).enumerate() {
+            if n > 0 && matches!(border, Border::Right(i) | Border::Below(i) if i % self.size == 0)
+            {
+                f.write_str("\n")?;
+            }
+            let dot = self.dots.iter().find(|&&(b, _)| b == border);
+            f.write_str(match dot {
+                Some((_, Dot::White)) => "o",
+                Some((_, Dot::Black)) => "x",
+                None => ".",
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Kropki {
+    type Err = SudokuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut dots = Vec::new();
+        let mut len = 0;
+
+        for (position, character) in s.chars().enumerate() {
+            let dot = match character {
+                '\n' | '\r' => continue,
+                'o' => Some(Dot::White),
+                'x' => Some(Dot::Black),
+                '.' => None,
+                _ => {
+                    return Err(SudokuError::InvalidCharacter {
+                        position,
+                        character,
+                    })
+                }
+            };
+            dots.extend(dot.map(|dot| (len, dot)));
+            len += 1;
+        }
+
+        // A grid of `size` rows has `size - 1` borders within each row, and
+        // as many within each column.
+        let size = (1..).find(|size| 2 * size * (size - 1) >= len).unwrap();
+        if len != 2 * size * (size - 1) {
+            return Err(SudokuError::InvalidLength {
+                expected: 2 * size * (size - 1),
+                found: len,
+            });
+        }
+
+        let borders = Border::all(size).collect::<Vec<_>>();
+        let dots = dots.into_iter().map(|(n, dot)| (borders[n], dot)).collect();
+
+        Self::new(size, dots)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraint::Constrained;
+    use crate::logic::{LogicalSolver, Technique};
+    use crate::sudoku::{GroupKind, Sudoku};
+    use crate::variants::{BoxSudoku, StandardSudoku};
+
+    const DOTS: &str = "....oo..
+o........
+.o.o...x
+...x...o.
+o...o...
+.....o..o
+..o.....
+o.oo...o.
+.....x.o
+.....o...
+..o..xo.
+o.......o
+o..o....
+.....oooo
+.o.....o
+o..o.....
+o.ox..oo";
+
+    #[test]
+    fn borders() {
+        let borders = Border::all(3).collect::<Vec<_>>();
+        assert_eq!(borders.len(), 12);
+        assert_eq!(
+            borders[..4],
+            [
+                Border::Right(0),
+                Border::Right(1),
+                Border::Below(0),
+                Border::Below(1)
+            ]
+        );
+        assert_eq!(borders[11], Border::Right(7));
+
+        assert_eq!(Border::Right(7).cells(9), Some((7, 8)));
+        assert_eq!(Border::Below(71).cells(9), Some((71, 80)));
+        assert_eq!(Border::Right(8).cells(9), None);
+        assert_eq!(Border::Below(72).cells(9), None);
+        assert_eq!(Border::Right(81).cells(9), None);
+
+        assert!(Dot::White.allows(4, 3));
+        assert!(!Dot::White.allows(4, 2));
+        assert!(Dot::Black.allows(4, 2));
+        assert!(Dot::Black.allows(1, 2));
+        assert!(!Dot::Black.allows(3, 9));
+    }
+
+    #[test]
+    fn parse() {
+        let kropki = Kropki::from_str(DOTS).unwrap();
+        assert_eq!(kropki.dots().len(), 40);
+        assert_eq!(kropki.dots()[0], (Border::Right(4), Dot::White));
+        assert_eq!(kropki.dots()[6], (Border::Below(12), Dot::Black));
+        assert_eq!(kropki.to_string(), DOTS);
+        assert_eq!(Kropki::from_str(&DOTS.replace('\n', "")), Ok(kropki));
+
+        assert_eq!(
+            Kropki::from_str("o.\n..*\n.."),
+            Err(SudokuError::InvalidCharacter {
+                position: 5,
+                character: '*'
+            })
+        );
+        assert_eq!(
+            Kropki::from_str(&DOTS[1..]),
+            Err(SudokuError::InvalidLength {
+                expected: 144,
+                found: 143
+            })
+        );
+        assert_eq!(
+            Kropki::new(
+                9,
+                vec![
+                    (Border::Below(0), Dot::White),
+                    (Border::Right(8), Dot::Black)
+                ]
+            ),
+            Err(SudokuError::InvalidDot { dot: 1 })
+        );
+        assert_eq!(
+            Kropki::new(
+                9,
+                vec![
+                    (Border::Right(0), Dot::White),
+                    (Border::Right(0), Dot::Black)
+                ]
+            ),
+            Err(SudokuError::InvalidDot { dot: 1 })
+        );
+    }
+
+    #[test]
+    fn rules() {
+        let kropki = Kropki::new(
+            3,
+            vec![
+                (Border::Right(0), Dot::Black),
+                (Border::Below(0), Dot::White),
+            ],
+        )
+        .unwrap();
+        let mut cells = vec![None; 9];
+        cells[0] = Some(3);
+
+        let mut candidates = vec![Candidates::from_range(1..=9); 9];
+        candidates[0] = Candidates::single(3);
+        kropki.propagate(&cells, &mut candidates);
+        assert_eq!(candidates[1], Candidates::single(6));
+        assert_eq!(candidates[3], Candidates::from_digits([2, 4]));
+        assert_eq!(candidates[4], Candidates::from_range(1..=9));
+
+        NonConsecutive.propagate(&cells, &mut candidates);
+        assert!(candidates[3].is_empty());
+
+        cells[1] = Some(5);
+        cells[3] = Some(4);
+        cells[4] = Some(6);
+        assert_eq!(kropki.check(&cells), [(0, 1)]);
+        assert_eq!(NonConsecutive.check(&cells), [(0, 3), (1, 4)]);
+    }
+
+    #[test]
+    fn non_consecutive() {
+        let mut game = Constrained::new(
+            StandardSudoku::from_str(
+                "    7     6     92       3    3         2   3     7  6          8     2  2  6   1",
+            )
+            .unwrap(),
+        )
+        .with(NonConsecutive);
+
+        game.solve().unwrap();
+        assert!(NonConsecutive.check(game.cells()).is_empty());
+        assert_eq!(
+            game.to_string(),
+            "135279468468513792792846135246381579579624813813957246351792684684135927927468351"
+        );
+    }
+
+    #[test]
+    fn logic() {
+        // The cells next to a 5 can't have a 4 or a 6.
+        let mut game = Constrained::new(StandardSudoku::new()).with(NonConsecutive);
+        game.set(40, Some(5));
+        let step = LogicalSolver::new(&game).unwrap().step().unwrap();
+        assert_eq!(step.technique, Technique::Constraint);
+        assert_eq!(step.houses, [GroupKind::Constraint(0)]);
+        assert_eq!(
+            step.eliminations,
+            [31, 39, 41, 49].map(|i| [(i, 4), (i, 6)]).concat()
+        );
+
+        // Only 6 can go on the other side of a black dot from a 3.
+        let dots = Kropki::new(9, vec![(Border::Right(40), Dot::Black)]).unwrap();
+        let mut game = Constrained::new(StandardSudoku::new()).with(dots);
+        game.set(40, Some(3));
+        let step = LogicalSolver::new(&game).unwrap().step().unwrap();
+        assert_eq!(step.technique, Technique::Constraint);
+        assert_eq!(
+            step.eliminations,
+            [1, 2, 4, 5, 7, 8, 9].map(|digit| (41, digit))
+        );
+    }
+
+    #[test]
+    fn kropki() {
+        let mut game =
+            Constrained::new(StandardSudoku::new()).with(Kropki::from_str(DOTS).unwrap());
+        game.set(49, Some(7));
+        assert!(game.has_unique_solution());
+
+        game.solve().unwrap();
+        assert!(game.solved());
+        assert_eq!(
+            game.to_string(),
+            "827154396965327148341689752593468271472513689618972435786235914154796823239841567"
+        );
+
+        let mut game =
+            Constrained::new(StandardSudoku::new()).with(Kropki::from_str(DOTS).unwrap());
+        game.set(4, Some(1));
+        game.set(5, Some(3));
+        assert!(!game.legal());
+        let conflicts = game.conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].cells, (4, 5));
+        assert_eq!(conflicts[0].group, 27);
+        assert_eq!(conflicts[0].kind, GroupKind::Constraint(0));
+    }
+
+    #[test]
+    fn sizes() {
+        // On a 9x9 grid, the border below cell 0 of a 4x4 grid would join
+        // cells 0 and 4.
+        let small = Kropki::new(4, vec![(Border::Below(0), Dot::White)]).unwrap();
+        assert_eq!(
+            Constrained::new(StandardSudoku::new())
+                .try_with(small.clone())
+                .unwrap_err(),
+            SudokuError::InvalidSize {
+                expected: 4,
+                found: 9
+            }
+        );
+        assert!(Constrained::new(BoxSudoku::<2, 2, 16>::new())
+            .try_with(small)
+            .is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "constraint for a grid of 4 rows cannot be added to one of 9")]
+    fn wrong_size() {
+        let small = Kropki::new(4, Vec::new()).unwrap();
+        Constrained::new(StandardSudoku::new()).with(small);
+    }
+}
@@ -0,0 +1,225 @@
+use super::{Candidates, Constraint};
+use crate::sudoku::Cell;
+
+/// The moves of a knight, as `(rows, columns)`.
+const KNIGHT: [(isize, isize); 8] = [
+    (-2, -1),
+    (-2, 1),
+    (-1, -2),
+    (-1, 2),
+    (1, -2),
+    (1, 2),
+    (2, -1),
+    (2, 1),
+];
+
+/// The moves of a king that aren't along a row or column, which already can't
+/// repeat a digit.
+const KING: [(isize, isize); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
+
+/// Get the cells that are a move in `moves` away from cell `i`, on a grid
+/// with `cells` cells.
+fn reach(cells: usize, i: usize, moves: &[(isize, isize)]) -> impl Iterator<Item = usize> + '_ {
+    let size = cells.isqrt();
+    let (row, col) = ((i / size) as isize, (i % size) as isize);
+
+    moves.iter().filter_map(move |(r, c)| {
+        let (row, col) = (row + r, col + c);
+        let inside = (0..size as isize).contains(&row) && (0..size as isize).contains(&col);
+        inside.then(|| row as usize * size + col as usize)
+    })
+}
+
+/// Find every pair of set cells a move apart that have the same digit.
+fn check(cells: &[Cell], moves: &[(isize, isize)]) -> Vec<(usize, usize)> {
+    let mut clashes = Vec::new();
+
+    for (i, &cell) in cells.iter().enumerate() {
+        if cell.is_none() {
+            continue;
+        }
+        for j in reach(cells.len(), i, moves).filter(|&j| j > i) {
+            if cells[j] == cell {
+                clashes.push((i, j));
+            }
+        }
+    }
+
+    clashes
+}
+
+/// Remove the digit of every set cell from the cells a move away from it.
+fn propagate(cells: &[Cell], candidates: &mut [Candidates], moves: &[(isize, isize)]) {
+    for (i, cell) in cells.iter().enumerate() {
+        if let Some(digit) = *cell {
+            for j in reach(cells.len(), i, moves) {
+                candidates[j].remove(digit);
+            }
+        }
+    }
+}
+
+/// Cells a knight's move apart can't have the same digit.
+///
+/// ```
+/// use sudoku_solver::constraint::{AntiKnight, Constrained};
+/// use sudoku_solver::{StandardSudoku, Sudoku};
+///
+/// let mut game = Constrained::new(StandardSudoku::new()).with(AntiKnight);
+/// game.set(0, Some(1));
+/// game.set(11, Some(1));
+/// assert_eq!(game.conflicts()[0].cells, (0, 11));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AntiKnight;
+
+impl Constraint for AntiKnight {
+    fn explain(&self) -> String {
+        "cells a knight's move apart can't have the same digit".to_string()
+    }
+
+    fn check(&self, cells: &[Cell]) -> Vec<(usize, usize)> {
+        check(cells, &KNIGHT)
+    }
+
+    fn propagate(&self, cells: &[Cell], candidates: &mut [Candidates]) {
+        propagate(cells, candidates, &KNIGHT)
+    }
+}
+
+/// Cells a king's move apart can't have the same digit, which on top of the
+/// rows and columns means that cells touching at a corner can't.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AntiKing;
+
+impl Constraint for AntiKing {
+    fn explain(&self) -> String {
+        "cells a king's move apart can't have the same digit".to_string()
+    }
+
+    fn check(&self, cells: &[Cell]) -> Vec<(usize, usize)> {
+        check(cells, &KING)
+    }
+
+    fn propagate(&self, cells: &[Cell], candidates: &mut [Candidates]) {
+        propagate(cells, candidates, &KING)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraint::Constrained;
+    use crate::logic::{LogicalSolver, Technique};
+    use crate::sudoku::{GroupKind, Sudoku};
+    use crate::variants::{HyperSudoku, StandardSudoku};
+    use std::str::FromStr;
+
+    #[test]
+    fn moves() {
+        let mut corner = reach(81, 0, &KNIGHT).collect::<Vec<_>>();
+        corner.sort_unstable();
+        assert_eq!(corner, [11, 19]);
+        assert_eq!(reach(81, 40, &KNIGHT).count(), 8);
+        assert_eq!(reach(81, 40, &KING).collect::<Vec<_>>(), [30, 32, 48, 50]);
+        assert_eq!(reach(16, 3, &KING).collect::<Vec<_>>(), [6]);
+    }
+
+    #[test]
+    fn rules() {
+        let mut cells = vec![None; 81];
+        cells[0] = Some(1);
+        cells[11] = Some(1);
+        cells[10] = Some(1);
+        assert_eq!(AntiKnight.check(&cells), [(0, 11)]);
+        assert_eq!(AntiKing.check(&cells), [(0, 10)]);
+
+        let mut candidates = vec![Candidates::from_range(1..=9); 81];
+        AntiKing.propagate(&cells, &mut candidates);
+        assert!(!candidates[10].contains(1));
+        assert!(!candidates[20].contains(1));
+        assert!(candidates[4].contains(1));
+    }
+
+    #[test]
+    fn anti_knight() {
+        let mut game = Constrained::new(
+            StandardSudoku::from_str(
+                "  97 4  6  6                 8   2    1         9      5   6           34        ",
+            )
+            .unwrap(),
+        )
+        .with(AntiKnight);
+
+        game.solve().unwrap();
+        assert!(AntiKnight.check(game.cells()).is_empty());
+        assert_eq!(
+            game.to_string(),
+            "189754326326189457745632189978365214261478935534921678853296741612847593497513862"
+        );
+    }
+
+    #[test]
+    fn anti_king() {
+        let mut game = Constrained::new(
+            HyperSudoku::from_str(
+                "      8         4      1 2 7 6   3    83     9   5    6              1      7    ",
+            )
+            .unwrap(),
+        )
+        .with(AntiKing);
+
+        game.solve().unwrap();
+        assert!(AntiKing.check(game.cells()).is_empty());
+        assert_eq!(
+            game.to_string(),
+            "562794813173826549849531726726149385458367291931258467687413952395682174214975638"
+        );
+    }
+
+    #[test]
+    fn logic() {
+        // A digit in the middle is taken from the eight cells a knight's move
+        // away, none of which share a house with it.
+        let mut game = Constrained::new(StandardSudoku::new()).with(AntiKnight);
+        game.set(40, Some(5));
+
+        let mut solver = LogicalSolver::new(&game).unwrap();
+        let step = solver.step().unwrap();
+        assert_eq!(step.technique, Technique::Constraint);
+        assert_eq!(step.houses, [GroupKind::Constraint(0)]);
+        assert_eq!(
+            step.eliminations,
+            [21, 23, 29, 33, 47, 51, 57, 59].map(|i| (i, 5))
+        );
+    }
+
+    #[test]
+    fn conflicts() {
+        let mut game = Constrained::new(HyperSudoku::new())
+            .with(AntiKnight)
+            .with(AntiKing);
+        game.set(40, Some(6));
+        game.set(59, Some(6));
+        game.set(30, Some(5));
+        game.set(38, Some(5));
+        assert!(!game.legal());
+
+        let conflicts = game.conflicts();
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(conflicts[0].cells, (40, 59));
+        assert_eq!(conflicts[0].group, 31);
+        assert_eq!(conflicts[0].kind, GroupKind::Constraint(0));
+        assert_eq!(conflicts[1].cells, (30, 38));
+        assert_eq!(conflicts[1].kind, GroupKind::Constraint(1));
+        assert_eq!(
+            conflicts[1].to_string(),
+            "cells 30 and 38 clash in constraint 1"
+        );
+        assert_eq!(
+            game.constraints()[1].explain(),
+            "cells a king's move apart can't have the same digit"
+        );
+        assert!(game.solve().is_err());
+    }
+}
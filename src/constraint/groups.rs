@@ -0,0 +1,98 @@
+use super::Constraint;
+use crate::sudoku::GroupKind;
+
+/// The two main diagonals must have every digit once, like in
+/// [`DiagonalSudoku`](crate::variants::DiagonalSudoku).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Diagonals;
+
+impl Constraint for Diagonals {
+    fn explain(&self) -> String {
+        "the digits on each of the two main diagonals must all be different".to_string()
+    }
+
+    /// Get the diagonal from the top left corner, then the one from the top
+    /// right corner, both from top to bottom.
+    fn groups(&self, size: usize) -> Vec<(GroupKind, Vec<usize>)> {
+        let main = (0..size).map(|row| row * size + row).collect();
+        let anti = (0..size).map(|row| row * size + size - 1 - row).collect();
+
+        vec![
+            (GroupKind::Diagonal(0), main),
+            (GroupKind::Diagonal(1), anti),
+        ]
+    }
+}
+
+/// The windows between the subgrids must have every digit once, like in
+/// [`HyperSudoku`](crate::variants::HyperSudoku).
+///
+/// The windows are square, with a single row or column between them and from
+/// the edge of the grid, and have a cell for every digit. Only grids whose
+/// side is a square number have room for them, and other grids get no
+/// windows at all.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Windows;
+
+impl Constraint for Windows {
+    fn explain(&self) -> String {
+        "the digits in each of the windows between the subgrids must all be different".to_string()
+    }
+
+    /// Get the windows from left to right, top to bottom.
+    fn groups(&self, size: usize) -> Vec<(GroupKind, Vec<usize>)> {
+        let side = size.isqrt();
+        if side * side != size {
+            return Vec::new();
+        }
+
+        let starts = (1..size)
+            .step_by(side + 1)
+            .filter(|start| start + side < size)
+            .collect::<Vec<_>>();
+
+        let mut windows = Vec::new();
+        for &row in &starts {
+            for &col in &starts {
+                let cells = (0..side * side)
+                    .map(|j| (row + j / side) * size + col + j % side)
+                    .collect();
+                windows.push((GroupKind::Window(windows.len()), cells));
+            }
+        }
+
+        windows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraint::Constrained;
+    use crate::variants::{HyperSudoku, MiniSudoku};
+    use crate::Sudoku;
+
+    #[test]
+    fn groups() {
+        let diagonals = Diagonals.groups(4);
+        assert_eq!(diagonals[0], (GroupKind::Diagonal(0), vec![0, 5, 10, 15]));
+        assert_eq!(diagonals[1], (GroupKind::Diagonal(1), vec![3, 6, 9, 12]));
+
+        let hyper = HyperSudoku::new().group_indices();
+        let windows = Windows.groups(9);
+        assert_eq!(windows.len(), 4);
+        for (n, (kind, cells)) in windows.into_iter().enumerate() {
+            assert_eq!(kind, GroupKind::Window(n));
+            assert_eq!(cells, hyper[27 + n]);
+        }
+
+        assert_eq!(Windows.groups(16).len(), 9);
+        assert_eq!(Windows.groups(16)[8].1[0], 11 * 16 + 11);
+
+        // Windows of four cells can't hold six digits.
+        assert!(Windows.groups(6).is_empty());
+        let mut mini = Constrained::new(MiniSudoku::new()).with(Windows);
+        mini.solve().unwrap();
+        assert!(mini.solved());
+    }
+}
@@ -0,0 +1,345 @@
+//! Rules that can be added on top of any grid.
+//!
+//! Every variant comes with its own groups, but many puzzles mix the rules of
+//! several variants, or add rules that aren't about groups at all. A
+//! [`Constraint`] is one such rule, and [`Constrained`] puts any number of
+//! them on top of a grid, which the solvers, the legality checks and the
+//! conflict reports all take into account.
+//!
+//! ```
+//! use std::str::FromStr;
+//! use sudoku_solver::constraint::{Constrained, Diagonals};
+//! use sudoku_solver::variants::HyperSudoku;
+//! use sudoku_solver::Sudoku;
+//!
+//! let hyper = HyperSudoku::from_str(
+//!     "       1   2    34    51        65   7 3   8   3          8    58    9  69       ",
+//! )
+//! .unwrap();
+//! let mut game = Constrained::new(hyper).with(Diagonals);
+//! assert_eq!(game.groups().len(), 9 * 3 + 4 + 2);
+//!
+//! // The windows and diagonals together leave the puzzle without a solution.
+//! assert!(game.solve().is_err());
+//! ```
+
+mod borders;
+mod chess;
+mod groups;
+
+pub use crate::solver::Candidates;
+pub use borders::*;
+pub use chess::*;
+pub use groups::*;
+
+use crate::error::SudokuError;
+use crate::sudoku::{Cage, Cell, GroupKind, Sudoku};
+use std::fmt::{Debug, Display};
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+/// A rule of the game that can be added on top of a grid.
+///
+/// A constraint can add groups of its own, which must have every digit once
+/// like the rows and columns do, and it can have rules between cells that are
+/// checked and solved through [`Self::check`] and [`Self::propagate`].
+///
+/// The cells are always given in rows from top to bottom, for a square grid.
+pub trait Constraint: Debug + Send + Sync {
+    /// Describe the rule, so that a player can be told what a clash breaks.
+    fn explain(&self) -> String;
+
+    /// Get the number of rows of the only grid the constraint can be added
+    /// to, or `None` if it fits a grid of any size.
+    fn size(&self) -> Option<usize> {
+        None
+    }
+
+    /// Get the groups the constraint adds to a grid of `size` rows and
+    /// columns, along with their kind.
+    ///
+    /// Repeated digits in these groups are found like in any other group, so
+    /// they don't need to be checked again.
+    fn groups(&self, _size: usize) -> Vec<(GroupKind, Vec<usize>)> {
+        Vec::new()
+    }
+
+    /// Find every pair of set cells that break the rule together.
+    fn check(&self, _cells: &[Cell]) -> Vec<(usize, usize)> {
+        Vec::new()
+    }
+
+    /// Remove the digits that the rule rules out from `candidates`, given the
+    /// digits set so far.
+    ///
+    /// The candidates of a set cell are only its own digit, and taking that
+    /// away is how a contradiction is found. Candidates can only be removed,
+    /// never added back.
+    fn propagate(&self, _cells: &[Cell], _candidates: &mut [Candidates]) {}
+}
+
+/// A grid with constraints added on top of the rules of its variant.
+///
+/// ```
+/// use sudoku_solver::constraint::{Constrained, Diagonals, Windows};
+/// use sudoku_solver::{GroupKind, StandardSudoku, Sudoku};
+///
+/// let mut game = Constrained::new(StandardSudoku::new())
+///     .with(Windows)
+///     .with(Diagonals);
+/// assert_eq!(game.group_kind(27), GroupKind::Window(0));
+/// assert_eq!(game.group_kind(31), GroupKind::Diagonal(0));
+///
+/// game.solve().unwrap();
+/// assert!(game.solved());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Constrained<S> {
+    base: S,
+    constraints: Vec<Arc<dyn Constraint>>,
+    groups: Vec<(GroupKind, Vec<usize>)>,
+}
+
+impl<S: Sudoku> Constrained<S> {
+    /// Start from the rules of `base`, with its cells.
+    pub fn new(base: S) -> Self {
+        Self {
+            base,
+            constraints: Vec::new(),
+            groups: Vec::new(),
+        }
+    }
+
+    /// Add a constraint to the grid.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the constraint is made for a grid of another size; see
+    /// [`Self::try_with`] for a version that returns an error instead.
+    pub fn with(self, constraint: impl Constraint + 'static) -> Self {
+        match self.try_with(constraint) {
+            Ok(game) => game,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    /// Add a constraint to the grid.
+    ///
+    /// Returns [`SudokuError::InvalidSize`] if the constraint is made for a
+    /// grid of another size.
+    pub fn try_with(mut self, constraint: impl Constraint + 'static) -> Result<Self, SudokuError> {
+        let size = self.base.rows().len();
+        if let Some(expected) = constraint.size().filter(|&expected| expected != size) {
+            return Err(SudokuError::InvalidSize {
+                expected,
+                found: size,
+            });
+        }
+
+        self.groups.extend(constraint.groups(size));
+        self.constraints.push(Arc::new(constraint));
+        Ok(self)
+    }
+
+    /// Get the grid the constraints were added to.
+    pub fn base(&self) -> &S {
+        &self.base
+    }
+}
+
+impl<S: Sudoku> Sudoku for Constrained<S> {
+    fn get(&self, i: usize) -> Cell {
+        self.base.get(i)
+    }
+
+    fn set(&mut self, i: usize, num: Cell) {
+        self.base.set(i, num)
+    }
+
+    fn cells(&self) -> &[Cell] {
+        self.base.cells()
+    }
+
+    fn cells_mut(&mut self) -> &mut [Cell] {
+        self.base.cells_mut()
+    }
+
+    fn cell_values(&self) -> RangeInclusive<u8> {
+        self.base.cell_values()
+    }
+
+    fn rows(&self) -> Vec<Vec<Cell>> {
+        self.base.rows()
+    }
+
+    fn columns(&self) -> Vec<Vec<Cell>> {
+        self.base.columns()
+    }
+
+    fn grids(&self) -> Vec<Vec<Cell>> {
+        self.base.grids()
+    }
+
+    fn group_indices(&self) -> Vec<Vec<usize>> {
+        let mut v = self.base.group_indices();
+        v.extend(self.groups.iter().map(|(_, cells)| cells.clone()));
+        v
+    }
+
+    fn group_kind(&self, group: usize) -> GroupKind {
+        let count = self.base.group_indices().len();
+        match group.checked_sub(count) {
+            Some(n) => self.groups[n].0,
+            None => self.base.group_kind(group),
+        }
+    }
+
+    fn groups_of(&self, i: usize) -> Vec<Vec<Cell>> {
+        let mut v = self.base.groups_of(i);
+        for (_, cells) in self.groups.iter().filter(|(_, cells)| cells.contains(&i)) {
+            v.push(cells.iter().map(|&j| self.get(j)).collect());
+        }
+        v
+    }
+
+    fn groups(&self) -> Vec<Vec<Cell>> {
+        let mut v = self.base.groups();
+        for (_, cells) in &self.groups {
+            v.push(cells.iter().map(|&i| self.get(i)).collect());
+        }
+        v
+    }
+
+    fn cages(&self) -> Vec<Cage> {
+        self.base.cages()
+    }
+
+    fn constraints(&self) -> Vec<Arc<dyn Constraint>> {
+        let mut v = self.base.constraints();
+        v.extend(self.constraints.iter().cloned());
+        v
+    }
+}
+
+impl<S: Display> Display for Constrained<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.base.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::{LogicalSolver, Technique};
+    use crate::variants::{DiagonalSudoku, HyperSudoku, StandardSudoku};
+    use std::str::FromStr;
+
+    /// Two cells that can't have the same digit.
+    #[derive(Debug)]
+    struct Apart(usize, usize);
+
+    impl Constraint for Apart {
+        fn explain(&self) -> String {
+            format!("cells {} and {} must be different", self.0, self.1)
+        }
+
+        fn check(&self, cells: &[Cell]) -> Vec<(usize, usize)> {
+            match (cells[self.0], cells[self.1]) {
+                (Some(a), Some(b)) if a == b => vec![(self.0, self.1)],
+                _ => Vec::new(),
+            }
+        }
+
+        fn propagate(&self, cells: &[Cell], candidates: &mut [Candidates]) {
+            for (a, b) in [(self.0, self.1), (self.1, self.0)] {
+                if let Some(digit) = cells[a] {
+                    candidates[b].remove(digit);
+                }
+            }
+        }
+    }
+
+    const DIAGONAL: &str =
+        "2   16    18     6  798     21   8      4   23   2 1     2 5  7 7 86  9      4   ";
+
+    #[test]
+    fn groups() {
+        let diagonal = DiagonalSudoku::from_str(DIAGONAL).unwrap();
+        let game = Constrained::new(StandardSudoku::from_str(DIAGONAL).unwrap()).with(Diagonals);
+
+        assert_eq!(game.group_indices(), diagonal.group_indices());
+        assert_eq!(game.groups(), diagonal.groups());
+        assert_eq!(game.groups_of(40), diagonal.groups_of(40));
+        assert_eq!(game.group_kind(28), GroupKind::Diagonal(1));
+        assert_eq!(game.group_kind(3), GroupKind::Row(3));
+
+        let mut solved = game.clone();
+        solved.solve().unwrap();
+        let mut expected = diagonal;
+        expected.solve().unwrap();
+        assert_eq!(solved.cells(), expected.cells());
+        assert!(solved.solved());
+        assert_eq!(game.base().count_solutions(2), 2);
+    }
+
+    #[test]
+    fn combined() {
+        let hyper = HyperSudoku::from_str(
+            "       1   2    34    51        65   7 3   8   3          8    58    9  69       ",
+        )
+        .unwrap();
+        assert_eq!(
+            Constrained::new(hyper).with(Diagonals).count_solutions(1),
+            0
+        );
+
+        let mut game = Constrained::new(HyperSudoku::new()).with(Diagonals);
+        game.solve().unwrap();
+        assert!(game.solved());
+        assert!(game.base().solved());
+        assert!(game.conflicts().is_empty());
+    }
+
+    #[test]
+    fn rules() {
+        let mut game = Constrained::new(StandardSudoku::new()).with(Apart(0, 80));
+        game.set(0, Some(5));
+        game.set(80, Some(5));
+
+        assert!(!game.legal());
+        let conflicts = game.conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].cells, (0, 80));
+        assert_eq!(conflicts[0].group, 27);
+        assert_eq!(conflicts[0].kind, GroupKind::Constraint(0));
+        assert_eq!(
+            game.constraints()[0].explain(),
+            "cells 0 and 80 must be different"
+        );
+
+        game.set(80, None);
+        assert!(game.legal());
+        game.solve().unwrap();
+        assert!(game.solved());
+        assert_ne!(game.get(80), Some(5));
+
+        // Nested grids keep the constraints of the grid inside.
+        let nested = Constrained::new(game).with(Apart(1, 79));
+        assert_eq!(nested.constraints().len(), 2);
+        assert!(nested.solved());
+    }
+
+    #[test]
+    fn logic() {
+        let mut game = Constrained::new(StandardSudoku::new()).with(Apart(0, 80));
+        game.set(0, Some(5));
+
+        let mut solver = LogicalSolver::new(&game).unwrap();
+        assert!(!solver.unique());
+        let step = solver.step().unwrap();
+        assert_eq!(step.technique, Technique::Constraint);
+        assert_eq!(step.houses, [GroupKind::Constraint(0)]);
+        assert_eq!(step.eliminations, [(80, 5)]);
+        assert!(!solver.candidates(80).contains(&5));
+    }
+}
@@ -0,0 +1,111 @@
+//! Errors that can occur while reading or solving a Sudoku.
+
+use crate::sudoku::Conflict;
+use std::error::Error;
+use std::fmt::Display;
+
+/// An error from parsing or solving a Sudoku.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SudokuError {
+    /// A character in the input is not a digit, a space or a line break.
+    InvalidCharacter {
+        /// The position of the character in the input, counted in characters.
+        position: usize,
+        /// The offending character.
+        character: char,
+    },
+    /// The input does not have the right number of cells for the grid, or of
+    /// sums for its cages.
+    InvalidLength {
+        /// The number of cells on the grid, or of its cages.
+        expected: usize,
+        /// The number of cells or sums in the input.
+        found: usize,
+    },
+    /// The regions of a grid do not split it into connected areas with one
+    /// cell for every digit.
+    InvalidRegion {
+        /// The region, counted from 0 in the order the regions first appear.
+        region: usize,
+    },
+    /// A cage is not a connected area of cells outside every other cage, or
+    /// its digits cannot add up to its sum.
+    InvalidCage {
+        /// The cage, counted from 0 in the order the cages first appear.
+        cage: usize,
+    },
+    /// A dot is not on a border between two cells of the grid, or shares its
+    /// border with another dot.
+    InvalidDot {
+        /// The dot, counted from 0 in the order the dots are given.
+        dot: usize,
+    },
+    /// A constraint made for a grid of one size is added to a grid of another.
+    InvalidSize {
+        /// The number of rows of the grid the constraint is made for.
+        expected: usize,
+        /// The number of rows of the grid it is added to.
+        found: usize,
+    },
+    /// Some of the digits on the grid break the rules of the game.
+    IllegalPosition {
+        /// Every pair of cells that clash with each other.
+        conflicts: Vec<Conflict>,
+    },
+    /// There is no way to fill in the remaining cells.
+    Unsolvable,
+    /// There is more than one way to fill in the remaining cells.
+    MultipleSolutions,
+    /// The search gave up before finding a solution.
+    LimitExceeded {
+        /// The number of guesses the search was allowed to make.
+        limit: usize,
+    },
+}
+
+impl Display for SudokuError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidCharacter {
+                position,
+                character,
+            } => write!(f, "invalid character {character:?} at position {position}"),
+            Self::InvalidLength { expected, found } => {
+                write!(
+                    f,
+                    "invalid length, expected {expected} cells but found {found}"
+                )
+            }
+            Self::InvalidRegion { region } => {
+                write!(f, "region {region} is not a connected area of the right size")
+            }
+            Self::InvalidCage { cage } => {
+                write!(f, "cage {cage} is not a connected area that can add up to its sum")
+            }
+            Self::InvalidDot { dot } => {
+                write!(f, "dot {dot} is not on a border of its own between two cells")
+            }
+            Self::InvalidSize { expected, found } => {
+                write!(
+                    f,
+                    "constraint for a grid of {expected} rows cannot be added to one of {found}"
+                )
+            }
+            Self::IllegalPosition { conflicts } => {
+                f.write_str("cannot solve illegal position")?;
+                for (i, conflict) in conflicts.iter().enumerate() {
+                    let separator = if i == 0 { ": " } else { ", " };
+                    write!(f, "{separator}{conflict}")?;
+                }
+                Ok(())
+            }
+            Self::Unsolvable => f.write_str("suduko cannot be solved"),
+            Self::MultipleSolutions => f.write_str("suduko has more than one solution"),
+            Self::LimitExceeded { limit } => {
+                write!(f, "gave up solving after {limit} guesses")
+            }
+        }
+    }
+}
+
+impl Error for SudokuError {}
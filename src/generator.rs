@@ -0,0 +1,398 @@
+//! Making new puzzles.
+//!
+//! The generator first fills an empty grid with a random solution, by placing
+//! random digits on random cells until the grid only has a single way to be
+//! completed. It then takes clues away in a random order, putting each one
+//! back if the puzzle would have more than one solution without it. To keep
+//! the clues in a symmetric pattern, cells that map onto each other under the
+//! chosen [`Symmetry`] are taken away together.
+//!
+//! Every random choice comes from the [`Rng`] given to the generator, so
+//! seeding it the same way makes the same puzzles.
+//!
+//! To make puzzles of a certain difficulty, the generator can keep making and
+//! [rating](crate::logic::rate) puzzles until one scores inside a range, or
+//! it runs out of its [`Budget`].
+
+use crate::logic::{rate, Rating};
+use crate::sudoku::{remove_clues, Sudoku};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+/// A way of mirroring or rotating the grid that the clues of a puzzle are
+/// laid out to match.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    /// The clues can be anywhere.
+    #[default]
+    None,
+    /// The clues look the same after turning the grid half a turn.
+    Rotational180,
+    /// The clues look the same after turning the grid a quarter turn.
+    Rotational90,
+    /// The clues look the same after flipping the grid upside down.
+    Horizontal,
+    /// The clues look the same after flipping the grid left to right.
+    Vertical,
+    /// The clues look the same after mirroring the grid along the diagonal
+    /// from the top left to the bottom right.
+    Diagonal,
+    /// The clues look the same after mirroring the grid along the diagonal
+    /// from the top right to the bottom left.
+    AntiDiagonal,
+}
+
+impl Symmetry {
+    /// Get every cell that cell `i` of a grid with `n` rows and columns maps
+    /// onto, including itself, in ascending order.
+    pub fn orbit(&self, i: usize, n: usize) -> Vec<usize> {
+        let (r, c) = (i / n, i % n);
+        let m = n - 1;
+
+        let cells = match self {
+            Self::None => vec![(r, c)],
+            Self::Rotational180 => vec![(r, c), (m - r, m - c)],
+            Self::Rotational90 => vec![(r, c), (c, m - r), (m - r, m - c), (m - c, r)],
+            Self::Horizontal => vec![(r, c), (m - r, c)],
+            Self::Vertical => vec![(r, c), (r, m - c)],
+            Self::Diagonal => vec![(r, c), (c, r)],
+            Self::AntiDiagonal => vec![(r, c), (m - c, m - r)],
+        };
+
+        let mut orbit = cells
+            .into_iter()
+            .map(|(r, c)| r * n + c)
+            .collect::<Vec<_>>();
+        orbit.sort_unstable();
+        orbit.dedup();
+        orbit
+    }
+
+    /// Split the cells of a grid with `n` rows and columns into the groups of
+    /// cells that map onto each other.
+    fn orbits(&self, n: usize) -> Vec<Vec<usize>> {
+        (0..n * n)
+            .map(|i| (i, self.orbit(i, n)))
+            .filter(|(i, orbit)| orbit[0] == *i)
+            .map(|(_, orbit)| orbit)
+            .collect()
+    }
+}
+
+/// How long to keep looking for a puzzle of the right difficulty.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Budget {
+    /// Give up after generating this many puzzles.
+    Attempts(usize),
+    /// Give up once this much time has passed. The puzzle being generated
+    /// when time runs out is still finished and rated.
+    Time(Duration),
+}
+
+/// A generated puzzle along with its rating.
+#[derive(Clone, Debug)]
+pub struct Rated<S> {
+    /// The puzzle.
+    pub puzzle: S,
+    /// How hard the puzzle is.
+    pub rating: Rating,
+}
+
+/// A generator of random puzzles with a single solution.
+///
+/// ```
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+/// use sudoku_solver::generator::Generator;
+/// use sudoku_solver::{StandardSudoku, Sudoku};
+///
+/// let mut generator = Generator::new(StdRng::seed_from_u64(7));
+/// let puzzle: StandardSudoku = generator.generate();
+/// assert!(puzzle.has_unique_solution());
+/// ```
+pub struct Generator<R> {
+    rng: R,
+    symmetry: Symmetry,
+}
+
+impl<R: Rng> Generator<R> {
+    /// Create a generator taking its random choices from `rng`.
+    pub fn new(rng: R) -> Self {
+        Self {
+            rng,
+            symmetry: Symmetry::None,
+        }
+    }
+
+    /// Lay out the clues of the generated puzzles to match `symmetry`.
+    pub fn with_symmetry(mut self, symmetry: Symmetry) -> Self {
+        self.symmetry = symmetry;
+        self
+    }
+
+    /// Create a random, completely filled grid.
+    pub fn solution<S: Sudoku + Default>(&mut self) -> S {
+        let mut sudoku = S::default();
+
+        let mut order = (0..sudoku.cells().len()).collect::<Vec<_>>();
+        order.shuffle(&mut self.rng);
+        let mut digits = sudoku.cell_values().collect::<Vec<_>>();
+
+        for i in order {
+            if sudoku.has_unique_solution() {
+                break;
+            }
+
+            digits.shuffle(&mut self.rng);
+            for &digit in &digits {
+                sudoku.set(i, Some(digit));
+                if sudoku.count_solutions(1) == 1 {
+                    break;
+                }
+                sudoku.set(i, None);
+            }
+        }
+
+        sudoku
+            .solve()
+            .expect("an empty grid and random clues that keep it solvable can always be solved");
+        sudoku
+    }
+
+    /// Create a random puzzle with a single solution.
+    ///
+    /// Without a symmetry the puzzle is minimal, so taking away any more of
+    /// its clues would give it more than one solution. With one, no more
+    /// groups of mirrored cells can be taken away.
+    pub fn generate<S: Sudoku + Default>(&mut self) -> S {
+        let solution = self.solution();
+        self.remove_clues(solution)
+    }
+
+    /// Create random puzzles until one has a score within `target`.
+    ///
+    /// The [score ranges](crate::logic::Tier::scores) of the tiers can be
+    /// used to ask for a named difficulty. Minimal puzzles are rarely easy,
+    /// so when a puzzle is too hard, clues from its solution are given back
+    /// until it scores below the end of the range.
+    ///
+    /// At least one puzzle is always made, and if none of them hit the target
+    /// before the budget runs out the one with the closest score is returned
+    /// as an error.
+    ///
+    /// ```
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    /// use sudoku_solver::generator::{Budget, Generator};
+    /// use sudoku_solver::logic::Tier;
+    /// use sudoku_solver::StandardSudoku;
+    ///
+    /// let mut generator = Generator::new(StdRng::seed_from_u64(7));
+    /// let rated = generator
+    ///     .generate_rated::<StandardSudoku>(Tier::Medium.scores(), Budget::Attempts(100))
+    ///     .unwrap();
+    /// assert_eq!(rated.rating.tier, Tier::Medium);
+    /// ```
+    pub fn generate_rated<S: Sudoku + Default>(
+        &mut self,
+        target: Range<f32>,
+        budget: Budget,
+    ) -> Result<Rated<S>, Rated<S>> {
+        let distance = |rating: &Rating| {
+            if rating.score < target.start {
+                target.start - rating.score
+            } else if rating.score >= target.end {
+                rating.score - target.end + f32::EPSILON
+            } else {
+                0.0
+            }
+        };
+
+        let start = Instant::now();
+        let mut best: Option<Rated<S>> = None;
+        let mut attempts = 0;
+
+        loop {
+            let (puzzle, rating) = self.generate_below::<S>(target.end);
+            attempts += 1;
+
+            if distance(&rating) == 0.0 {
+                return Ok(Rated { puzzle, rating });
+            }
+            if best
+                .as_ref()
+                .is_none_or(|best| distance(&rating) < distance(&best.rating))
+            {
+                best = Some(Rated { puzzle, rating });
+            }
+
+            let spent = match budget {
+                Budget::Attempts(limit) => attempts >= limit,
+                Budget::Time(limit) => start.elapsed() >= limit,
+            };
+            if spent {
+                return Err(best.expect("at least one puzzle has been made"));
+            }
+        }
+    }
+
+    /// Create a random puzzle, then give back clues from its solution until
+    /// it scores below `limit` or no clues are left to give back.
+    fn generate_below<S: Sudoku + Default>(&mut self, limit: f32) -> (S, Rating) {
+        let solution = self.solution::<S>();
+        let mut puzzle = self.remove_clues(solution.clone());
+        let mut rating = rate(&puzzle).expect("generated puzzles are always legal");
+
+        let mut removed = self
+            .symmetry
+            .orbits(puzzle.rows().len())
+            .into_iter()
+            .filter(|orbit| puzzle.get(orbit[0]).is_none())
+            .collect::<Vec<_>>();
+        removed.shuffle(&mut self.rng);
+
+        while rating.score >= limit {
+            let Some(orbit) = removed.pop() else {
+                break;
+            };
+            for i in orbit {
+                puzzle.set(i, solution.get(i));
+            }
+            rating = rate(&puzzle).expect("generated puzzles are always legal");
+        }
+
+        (puzzle, rating)
+    }
+
+    /// Take away as many clues from `sudoku` as possible while keeping its
+    /// solution unique.
+    fn remove_clues<S: Sudoku>(&mut self, mut sudoku: S) -> S {
+        let mut orbits = self.symmetry.orbits(sudoku.rows().len());
+        orbits.shuffle(&mut self.rng);
+
+        remove_clues(&mut sudoku, orbits);
+        sudoku
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::Tier;
+    use crate::variants::{HyperSudoku, MiniSudoku, StandardSudoku};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn generator(seed: u64) -> Generator<StdRng> {
+        Generator::new(StdRng::seed_from_u64(seed))
+    }
+
+    #[test]
+    fn solution() {
+        let solution: StandardSudoku = generator(1).solution();
+        assert!(solution.solved());
+
+        let solution: HyperSudoku = generator(1).solution();
+        assert!(solution.solved());
+
+        let solution: MiniSudoku = generator(1).solution();
+        assert!(solution.solved());
+    }
+
+    #[test]
+    fn generate() {
+        let puzzle: StandardSudoku = generator(2).generate();
+        assert!(puzzle.has_unique_solution());
+        assert!(!puzzle.filled());
+
+        // Every clue is needed.
+        for i in (0..81).filter(|&i| puzzle.get(i).is_some()) {
+            let mut fewer = puzzle;
+            fewer.set(i, None);
+            assert!(!fewer.has_unique_solution());
+        }
+
+        let puzzle: HyperSudoku = generator(2).generate();
+        assert!(puzzle.has_unique_solution());
+
+        let puzzle: MiniSudoku = generator(2).generate();
+        assert!(puzzle.has_unique_solution());
+    }
+
+    #[test]
+    fn symmetry() {
+        assert_eq!(Symmetry::Rotational90.orbit(1, 9), [1, 17, 63, 79]);
+        assert_eq!(Symmetry::Rotational180.orbit(40, 9), [40]);
+        assert_eq!(Symmetry::Diagonal.orbit(1, 9), [1, 9]);
+        assert_eq!(Symmetry::AntiDiagonal.orbit(0, 9), [0, 80]);
+        assert_eq!(Symmetry::Rotational90.orbits(9).len(), 21);
+
+        for symmetry in [
+            Symmetry::None,
+            Symmetry::Rotational180,
+            Symmetry::Rotational90,
+            Symmetry::Horizontal,
+            Symmetry::Vertical,
+            Symmetry::Diagonal,
+            Symmetry::AntiDiagonal,
+        ] {
+            let puzzle: StandardSudoku = generator(5).with_symmetry(symmetry).generate();
+            assert!(puzzle.has_unique_solution());
+            for i in 0..81 {
+                for j in symmetry.orbit(i, 9) {
+                    assert_eq!(puzzle.get(i).is_some(), puzzle.get(j).is_some());
+                }
+            }
+        }
+
+        let puzzle: MiniSudoku = generator(5)
+            .with_symmetry(Symmetry::Rotational90)
+            .generate();
+        assert!(puzzle.has_unique_solution());
+    }
+
+    #[test]
+    fn generate_rated() {
+        let rated = generator(6)
+            .generate_rated::<StandardSudoku>(Tier::Easy.scores(), Budget::Attempts(100))
+            .unwrap();
+        assert_eq!(rated.rating.tier, Tier::Easy);
+        assert_eq!(rate(&rated.puzzle).unwrap(), rated.rating);
+
+        let rated = generator(6)
+            .generate_rated::<StandardSudoku>(Tier::Hard.scores(), Budget::Attempts(100))
+            .unwrap();
+        assert_eq!(rated.rating.tier, Tier::Hard);
+    }
+
+    #[test]
+    fn budget() {
+        // Nothing scores that high, so the hardest puzzle comes back, which is
+        // at least as hard as the first one.
+        let first = generator(7)
+            .generate_rated::<StandardSudoku>(20.0..30.0, Budget::Attempts(1))
+            .unwrap_err();
+        let best = generator(7)
+            .generate_rated::<StandardSudoku>(20.0..30.0, Budget::Attempts(5))
+            .unwrap_err();
+        assert!(best.rating.score >= first.rating.score);
+        assert_eq!(rate(&best.puzzle).unwrap(), best.rating);
+
+        let best = generator(7)
+            .generate_rated::<MiniSudoku>(20.0..30.0, Budget::Time(Duration::ZERO))
+            .unwrap_err();
+        assert!(best.puzzle.has_unique_solution());
+    }
+
+    #[test]
+    fn reproducible() {
+        let a: StandardSudoku = generator(3).generate();
+        let b: StandardSudoku = generator(3).generate();
+        assert_eq!(a.to_string(), b.to_string());
+
+        let c: StandardSudoku = generator(4).generate();
+        assert_ne!(a.to_string(), c.to_string());
+    }
+}
@@ -7,13 +7,23 @@
 //! correct digits, without ever modifying the initially revealed digits.
 //!
 //! The game also has some variants, like mini-sudoku where the grid is
-//! smaller, or hyper-sudoku, where there are more sub-grids on the grid which
-//! further constrains which digits you can place.
+//! smaller, grids of other sizes with rectangular sub-grids, jigsaw-sudoku
+//! where the sub-grids are irregular shapes, diagonal-sudoku where the two
+//! main diagonals can't repeat a number either, killer-sudoku where cages of
+//! cells have to add up to a given sum, or hyper-sudoku, where there are more
+//! sub-grids on the grid which further constrains which digits you can place.
 
 #![warn(missing_docs)]
 
+pub mod constraint;
+pub mod error;
+pub mod generator;
+pub mod logic;
+mod solver;
 pub mod sudoku;
+pub mod transform;
 pub mod variants;
 
+pub use error::SudokuError;
 pub use sudoku::*;
 pub use variants::StandardSudoku;
@@ -0,0 +1,418 @@
+use super::{Board, ChainNode, LinkKind, Pattern, Step, Technique};
+use crate::solver::Candidates;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::collections::VecDeque;
+
+/// A candidate, as `(cell, digit)`.
+type Node = (usize, u8);
+
+/// Build a chain where the links alternate between strong and weak, starting
+/// with a strong link.
+pub(super) fn alternating(nodes: &[Node]) -> Vec<ChainNode> {
+    nodes
+        .iter()
+        .enumerate()
+        .map(|(n, &(cell, digit))| ChainNode {
+            cell,
+            digit,
+            link: match n {
+                0 => None,
+                n if n % 2 == 1 => Some(LinkKind::Strong),
+                _ => Some(LinkKind::Weak),
+            },
+        })
+        .collect()
+}
+
+/// Get every candidate that has to be true if `node` is false.
+///
+/// With `cells` set, this includes the other digit of a cell with only two
+/// digits left, and not just the other place of a digit in a group.
+fn strong_links(board: &Board, (cell, digit): Node, cells: bool) -> Vec<Node> {
+    let mut links = Vec::new();
+
+    let candidates = board.candidates(cell);
+    if cells && candidates.len() == 2 {
+        let other = candidates.difference(Candidates::single(digit));
+        links.push((cell, other.first().unwrap()));
+    }
+
+    for &h in board.houses_of(cell) {
+        if let [a, b] = board.places(&board.houses()[h], digit)[..] {
+            let other = if a == cell { b } else { a };
+            if !links.contains(&(other, digit)) {
+                links.push((other, digit));
+            }
+        }
+    }
+
+    links
+}
+
+/// Get every candidate that has to be false if `node` is true.
+fn weak_links(board: &Board, (cell, digit): Node, cells: bool) -> Vec<Node> {
+    let mut links = Vec::new();
+
+    if cells {
+        let others = board.candidates(cell).difference(Candidates::single(digit));
+        links.extend(others.iter().map(|d| (cell, d)));
+    }
+
+    links.extend(
+        board
+            .peers(cell)
+            .iter()
+            .filter(|&&p| board.cell(p).is_none() && board.candidates(p).contains(digit))
+            .map(|&p| (p, digit)),
+    );
+
+    links
+}
+
+/// Get the pencil marks that can be removed knowing that at least one of `a`
+/// and `b` is true.
+fn conclusions(board: &Board, a: Node, b: Node) -> Vec<Node> {
+    let ((a, x), (b, y)) = (a, b);
+
+    if x == y {
+        let targets = board
+            .empty_cells()
+            .filter(|&i| i != a && i != b && board.sees(i, a) && board.sees(i, b))
+            .collect::<Vec<_>>();
+        board.eliminations(targets, Candidates::single(x))
+    } else if a == b {
+        board.eliminations(
+            [a],
+            board.digits().difference(Candidates::from_digits([x, y])),
+        )
+    } else if board.sees(a, b) {
+        let mut eliminations = board.eliminations([a], Candidates::single(y));
+        eliminations.extend(board.eliminations([b], Candidates::single(x)));
+        eliminations
+    } else {
+        Vec::new()
+    }
+}
+
+/// Find the shortest chain from `start` that ends in a strong link and lets
+/// some pencil marks be removed.
+fn search(board: &Board, start: Node, cells: bool) -> Option<(Vec<ChainNode>, Vec<Node>)> {
+    // Every step of the search is a candidate along with whether the link
+    // leading to it was strong.
+    let mut parents = FxHashMap::default();
+    let mut queue = VecDeque::new();
+
+    for next in strong_links(board, start, cells) {
+        parents.insert((next, true), (start, false));
+        queue.push_back(((next, true), 1));
+    }
+
+    while let Some(((node, strong), len)) = queue.pop_front() {
+        if strong && len >= 3 {
+            let eliminations = conclusions(board, start, node);
+            if !eliminations.is_empty() {
+                let mut chain = Vec::new();
+                let mut state = (node, strong);
+                while let Some(&parent) = parents.get(&state) {
+                    chain.push(ChainNode {
+                        cell: state.0 .0,
+                        digit: state.0 .1,
+                        link: Some(if state.1 {
+                            LinkKind::Strong
+                        } else {
+                            LinkKind::Weak
+                        }),
+                    });
+                    state = parent;
+                }
+                chain.push(ChainNode {
+                    cell: start.0,
+                    digit: start.1,
+                    link: None,
+                });
+                chain.reverse();
+
+                return Some((chain, eliminations));
+            }
+        }
+
+        let links = if strong {
+            weak_links(board, node, cells)
+        } else {
+            strong_links(board, node, cells)
+        };
+
+        for next in links {
+            let state = (next, !strong);
+            if next != start && !parents.contains_key(&state) {
+                parents.insert(state, (node, strong));
+                queue.push_back((state, len + 1));
+            }
+        }
+    }
+
+    None
+}
+
+fn chain(board: &Board, cells: bool, technique: Technique) -> Option<Step> {
+    for start in board.empty_cells() {
+        for digit in board.candidates(start).iter() {
+            let Some((chain, eliminations)) = search(board, (start, digit), cells) else {
+                continue;
+            };
+
+            let mut cells = chain.iter().map(|node| node.cell).collect::<Vec<_>>();
+            cells.dedup();
+
+            let mut digits = chain.iter().map(|node| node.digit).collect::<Vec<_>>();
+            digits.sort_unstable();
+            digits.dedup();
+
+            return Some(Step {
+                technique,
+                houses: Vec::new(),
+                cells,
+                digits,
+                placements: Vec::new(),
+                eliminations,
+                pattern: Some(Pattern::Chain(chain)),
+            });
+        }
+    }
+
+    None
+}
+
+/// Find a chain of alternating strong and weak links between the places of
+/// a single digit.
+pub(super) fn x_chain(board: &Board) -> Option<Step> {
+    chain(board, false, Technique::XChain)
+}
+
+/// Find a chain of alternating strong and weak links, where links can be
+/// both between the places of a digit and between the digits of a cell.
+pub(super) fn alternating_inference_chain(board: &Board) -> Option<Step> {
+    chain(board, true, Technique::AlternatingInferenceChain)
+}
+
+/// Color the places of a digit in two colors, so that the two places of
+/// every group with only two places left get different colors. One of the
+/// colors has to be true.
+///
+/// If two places of the same color see each other, that color is false.
+/// Otherwise, any other place that sees both colors can't hold the digit.
+pub(super) fn simple_coloring(board: &Board) -> Option<Step> {
+    for digit in board.digits().iter() {
+        let mut colored = FxHashSet::default();
+
+        for root in board.empty_cells() {
+            if !board.candidates(root).contains(digit) || colored.contains(&root) {
+                continue;
+            }
+
+            // Color every place linked to the root, keeping the parent of
+            // each place to be able to trace back the chain.
+            let mut colors = FxHashMap::default();
+            let mut parents = FxHashMap::default();
+            let mut queue = VecDeque::from([root]);
+            colors.insert(root, true);
+
+            while let Some(cell) = queue.pop_front() {
+                for (next, _) in strong_links(board, (cell, digit), false) {
+                    if !colors.contains_key(&next) {
+                        colors.insert(next, !colors[&cell]);
+                        parents.insert(next, cell);
+                        queue.push_back(next);
+                    }
+                }
+            }
+            colored.extend(colors.keys().copied());
+
+            if colors.len() < 3 {
+                continue;
+            }
+
+            let mut cells = colors.keys().copied().collect::<Vec<_>>();
+            cells.sort_unstable();
+
+            // The chain between two places, through their closest common
+            // ancestor.
+            let path = |a: usize, b: usize| {
+                let ancestors = |mut i: usize| {
+                    let mut v = vec![i];
+                    while let Some(&p) = parents.get(&i) {
+                        v.push(p);
+                        i = p;
+                    }
+                    v
+                };
+
+                let from_a = ancestors(a);
+                let from_b = ancestors(b);
+                let common = from_a.iter().position(|i| from_b.contains(i)).unwrap();
+                let meet = from_b.iter().position(|&i| i == from_a[common]).unwrap();
+
+                let cells = from_a[..=common]
+                    .iter()
+                    .chain(from_b[..meet].iter().rev())
+                    .copied();
+                cells
+                    .enumerate()
+                    .map(|(n, cell)| ChainNode {
+                        cell,
+                        digit,
+                        link: (n > 0).then_some(LinkKind::Strong),
+                    })
+                    .collect::<Vec<_>>()
+            };
+
+            let step = |chain: Vec<ChainNode>, eliminations| Step {
+                technique: Technique::SimpleColoring,
+                houses: Vec::new(),
+                cells: cells.clone(),
+                digits: vec![digit],
+                placements: Vec::new(),
+                eliminations,
+                pattern: Some(Pattern::Chain(chain)),
+            };
+
+            // Two places of the same color seeing each other.
+            for (n, &a) in cells.iter().enumerate() {
+                for &b in &cells[n + 1..] {
+                    if colors[&a] == colors[&b] && board.sees(a, b) {
+                        let wrong = cells.iter().copied().filter(|i| colors[i] == colors[&a]);
+                        let eliminations = board.eliminations(wrong, Candidates::single(digit));
+                        return Some(step(path(a, b), eliminations));
+                    }
+                }
+            }
+
+            // Places outside the chain seeing both colors.
+            let seen = |target: usize, color: bool| {
+                cells
+                    .iter()
+                    .copied()
+                    .find(|&i| colors[&i] == color && board.sees(i, target))
+            };
+
+            let targets = board
+                .empty_cells()
+                .filter(|&i| board.candidates(i).contains(digit) && !colors.contains_key(&i))
+                .filter_map(|i| Some((i, seen(i, true)?, seen(i, false)?)))
+                .collect::<Vec<_>>();
+
+            if let Some(&(_, a, b)) = targets.first() {
+                let eliminations = targets.iter().map(|&(i, _, _)| (i, digit)).collect();
+                return Some(step(path(a, b), eliminations));
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variants::StandardSudoku;
+
+    /// Create an empty board where 1 can only go on the given cells of the
+    /// given rows.
+    fn board(rows: &[(usize, &[usize])]) -> Board {
+        let mut board = Board::new(&StandardSudoku::new());
+        for &(row, columns) in rows {
+            for col in 0..9 {
+                if !columns.contains(&col) {
+                    board.candidates[row * 9 + col].remove(1);
+                }
+            }
+        }
+        board
+    }
+
+    #[test]
+    fn x_chain() {
+        // A skyscraper: 1 is on r0c0 or r0c4, and on r8c0 or r8c5.
+        let board = board(&[(0, &[0, 4]), (8, &[0, 5])]);
+
+        let step = super::x_chain(&board).unwrap();
+        assert_eq!(step.technique, Technique::XChain);
+        assert_eq!(
+            step.pattern,
+            Some(Pattern::Chain(alternating(&[
+                (4, 1),
+                (0, 1),
+                (72, 1),
+                (77, 1)
+            ])))
+        );
+        assert_eq!(step.eliminations, [(14, 1), (23, 1), (58, 1), (67, 1)]);
+    }
+
+    #[test]
+    fn alternating_inference_chain() {
+        // r0c0 is 1 or 2, and 1 can only go on r0c0 or r4c0 in the first
+        // column, so either r0c0 is 2 or r4c0 is 1.
+        let mut board = board(&[]);
+        board.candidates[0] = Candidates::from_digits([1, 2]);
+        for row in 1..9 {
+            if row != 4 {
+                board.candidates[row * 9].remove(1);
+            }
+        }
+        board.candidates[36] = Candidates::from_digits([1, 2]);
+
+        let step = super::alternating_inference_chain(&board).unwrap();
+        assert_eq!(step.technique, Technique::AlternatingInferenceChain);
+        assert_eq!(step.cells, [0, 36]);
+        assert_eq!(
+            step.pattern,
+            Some(Pattern::Chain(alternating(&[
+                (0, 2),
+                (0, 1),
+                (36, 1),
+                (36, 2)
+            ])))
+        );
+        assert_eq!(
+            step.eliminations,
+            [9, 18, 27, 45, 54, 63, 72].map(|i| (i, 2))
+        );
+    }
+
+    #[test]
+    fn simple_coloring() {
+        // The places of 1 on r6c0, r0c0, r0c4 and r6c4 are linked through the
+        // first row and the first and fifth columns, so the two ends on the
+        // seventh row have different colors.
+        let mut board = board(&[(0, &[0, 4])]);
+        for row in 1..9 {
+            if row != 6 {
+                board.candidates[row * 9].remove(1);
+                board.candidates[row * 9 + 4].remove(1);
+            }
+        }
+
+        let step = super::simple_coloring(&board).unwrap();
+        assert_eq!(step.technique, Technique::SimpleColoring);
+        assert_eq!(step.cells, [0, 4, 54, 58]);
+        assert_eq!(
+            step.eliminations,
+            [55, 56, 57, 59, 60, 61, 62].map(|i| (i, 1))
+        );
+        assert_eq!(
+            step.pattern,
+            Some(Pattern::Chain(
+                [58, 4, 0, 54]
+                    .iter()
+                    .enumerate()
+                    .map(|(n, &cell)| ChainNode {
+                        cell,
+                        digit: 1,
+                        link: (n > 0).then_some(LinkKind::Strong),
+                    })
+                    .collect()
+            ))
+        );
+    }
+}
@@ -0,0 +1,44 @@
+use super::{Board, Step, Technique};
+use crate::sudoku::GroupKind;
+
+/// Find the pencil marks that one of the constraints of the grid rules out.
+pub(super) fn constraint(board: &Board) -> Option<Step> {
+    board
+        .constraints()
+        .iter()
+        .enumerate()
+        .find_map(|(n, constraint)| {
+            let mut candidates = board.candidates.clone();
+            constraint.propagate(&board.cells, &mut candidates);
+
+            let eliminations = board
+                .empty_cells()
+                .flat_map(|i| {
+                    board
+                        .candidates(i)
+                        .difference(candidates[i])
+                        .iter()
+                        .map(move |digit| (i, digit))
+                })
+                .collect::<Vec<_>>();
+            if eliminations.is_empty() {
+                return None;
+            }
+
+            let mut cells = eliminations.iter().map(|&(i, _)| i).collect::<Vec<_>>();
+            cells.dedup();
+            let mut digits = eliminations.iter().map(|&(_, d)| d).collect::<Vec<_>>();
+            digits.sort_unstable();
+            digits.dedup();
+
+            Some(Step {
+                technique: Technique::Constraint,
+                houses: vec![GroupKind::Constraint(n)],
+                cells,
+                digits,
+                placements: Vec::new(),
+                eliminations,
+                pattern: None,
+            })
+        })
+}
@@ -0,0 +1,240 @@
+use super::{combinations, Board, House, Pattern, Step, Technique};
+use crate::solver::Candidates;
+use crate::sudoku::GroupKind;
+
+pub(super) fn x_wing(board: &Board) -> Option<Step> {
+    fish(board, 2, false)
+}
+
+pub(super) fn swordfish(board: &Board) -> Option<Step> {
+    fish(board, 3, false)
+}
+
+pub(super) fn jellyfish(board: &Board) -> Option<Step> {
+    fish(board, 4, false)
+}
+
+pub(super) fn finned_x_wing(board: &Board) -> Option<Step> {
+    fish(board, 2, true)
+}
+
+pub(super) fn finned_swordfish(board: &Board) -> Option<Step> {
+    fish(board, 3, true)
+}
+
+pub(super) fn finned_jellyfish(board: &Board) -> Option<Step> {
+    fish(board, 4, true)
+}
+
+fn technique(n: usize, finned: bool, sashimi: bool) -> Technique {
+    match (n, finned, sashimi) {
+        (2, false, _) => Technique::XWing,
+        (3, false, _) => Technique::Swordfish,
+        (4, false, _) => Technique::Jellyfish,
+        (2, true, false) => Technique::FinnedXWing,
+        (3, true, false) => Technique::FinnedSwordfish,
+        (4, true, false) => Technique::FinnedJellyfish,
+        (2, true, true) => Technique::SashimiXWing,
+        (3, true, true) => Technique::SashimiSwordfish,
+        (4, true, true) => Technique::SashimiJellyfish,
+        _ => unreachable!("no fish of size {n}"),
+    }
+}
+
+/// Find `n` rows where every place of a digit lies in the same `n` columns,
+/// or the other way around.
+///
+/// Each base row has to hold the digit in one of the cover columns, so the
+/// digit can be removed from the rest of the cover columns. With
+/// `finned` set, the fish may also have places outside the cover columns, as
+/// long as every cell the digit is removed from sees all of those fins.
+fn fish(board: &Board, n: usize, finned: bool) -> Option<Step> {
+    let rows = lines(board, |kind| matches!(kind, GroupKind::Row(_)));
+    let columns = lines(board, |kind| matches!(kind, GroupKind::Column(_)));
+
+    for digit in board.digits().iter() {
+        for (base_lines, cover_lines) in [(&rows, &columns), (&columns, &rows)] {
+            let bases = base_lines
+                .iter()
+                .copied()
+                .filter(|house| {
+                    let places = board.places(house, digit).len();
+                    places > 0 && (finned || places <= n)
+                })
+                .collect::<Vec<_>>();
+
+            for base in combinations(&bases, n) {
+                let places = base
+                    .iter()
+                    .flat_map(|house| board.places(house, digit))
+                    .collect::<Vec<_>>();
+
+                let covering = cover_lines
+                    .iter()
+                    .copied()
+                    .filter(|house| places.iter().any(|i| house.cells.contains(i)))
+                    .collect::<Vec<_>>();
+
+                let covers = match covering.len() {
+                    len if len == n && !finned => vec![covering],
+                    len if len > n && finned => combinations(&covering, n),
+                    _ => continue,
+                };
+
+                for cover in covers {
+                    if let Some(step) = fish_step(board, digit, &base, &cover, &places, n, finned) {
+                        return Some(step);
+                    }
+                }
+            }
+        }
+    }
+
+    None
+}
+
+fn fish_step(
+    board: &Board,
+    digit: u8,
+    base: &[&House],
+    cover: &[&House],
+    places: &[usize],
+    n: usize,
+    finned: bool,
+) -> Option<Step> {
+    let covered = |i: &usize| cover.iter().any(|house| house.cells.contains(i));
+
+    let fins = places
+        .iter()
+        .copied()
+        .filter(|i| !covered(i))
+        .collect::<Vec<_>>();
+
+    // Only fins that see each other, through any house, can leave cells that
+    // see all of them. The targets are checked against every fin below, which
+    // is what keeps the step sound.
+    if fins
+        .iter()
+        .any(|&fin| fin != fins[0] && !board.sees(fin, fins[0]))
+    {
+        return None;
+    }
+
+    // Every base line needs a place in the cover, or it isn't part of the
+    // fish at all.
+    let base_covered = base
+        .iter()
+        .map(|house| {
+            house
+                .cells
+                .iter()
+                .filter(|i| places.contains(i) && covered(i))
+                .count()
+        })
+        .collect::<Vec<_>>();
+    if base_covered.contains(&0) {
+        return None;
+    }
+
+    let targets = cover
+        .iter()
+        .flat_map(|house| board.empty_in(house))
+        .filter(|i| !base.iter().any(|house| house.cells.contains(i)))
+        .filter(|&i| fins.iter().all(|&fin| board.sees(i, fin)));
+    let eliminations = board.eliminations(targets, Candidates::single(digit));
+    if eliminations.is_empty() {
+        return None;
+    }
+
+    let sashimi = finned && base_covered.contains(&1);
+
+    Some(Step {
+        technique: technique(n, finned, sashimi),
+        houses: base.iter().chain(cover).map(|house| house.kind).collect(),
+        cells: places.to_vec(),
+        digits: vec![digit],
+        placements: Vec::new(),
+        eliminations,
+        pattern: Some(Pattern::Fish {
+            base: base.iter().map(|house| house.kind).collect(),
+            cover: cover.iter().map(|house| house.kind).collect(),
+            fins,
+        }),
+    })
+}
+
+fn lines(board: &Board, kind: impl Fn(&GroupKind) -> bool) -> Vec<&House> {
+    board
+        .houses()
+        .iter()
+        .filter(|house| kind(&house.kind))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variants::StandardSudoku;
+
+    /// Create an empty board where 1 can only go on the given columns of the
+    /// given rows.
+    fn board(rows: &[(usize, &[usize])]) -> Board {
+        let mut board = Board::new(&StandardSudoku::new());
+        for &(row, columns) in rows {
+            for col in 0..9 {
+                if !columns.contains(&col) {
+                    board.candidates[row * 9 + col].remove(1);
+                }
+            }
+        }
+        board
+    }
+
+    #[test]
+    fn x_wing() {
+        let board = board(&[(0, &[0, 4]), (4, &[0, 4])]);
+
+        let step = super::x_wing(&board).unwrap();
+        assert_eq!(step.technique, Technique::XWing);
+        assert_eq!(step.cells, [0, 4, 36, 40]);
+        assert_eq!(
+            step.pattern,
+            Some(Pattern::Fish {
+                base: vec![GroupKind::Row(0), GroupKind::Row(4)],
+                cover: vec![GroupKind::Column(0), GroupKind::Column(4)],
+                fins: Vec::new(),
+            })
+        );
+        assert_eq!(step.eliminations.len(), 14);
+        assert!(super::finned_x_wing(&board).is_none());
+    }
+
+    #[test]
+    fn swordfish() {
+        let board = board(&[(1, &[2, 5]), (4, &[5, 8]), (7, &[2, 8])]);
+
+        let step = super::swordfish(&board).unwrap();
+        assert_eq!(step.technique, Technique::Swordfish);
+        assert_eq!(step.eliminations.len(), 18);
+    }
+
+    #[test]
+    fn finned_x_wing() {
+        let board = board(&[(0, &[0, 4]), (4, &[0, 4, 5])]);
+        assert!(super::x_wing(&board).is_none());
+
+        let step = super::finned_x_wing(&board).unwrap();
+        assert_eq!(step.technique, Technique::FinnedXWing);
+        assert_eq!(step.eliminations, [(31, 1), (49, 1)]);
+        assert!(matches!(step.pattern, Some(Pattern::Fish { fins, .. }) if fins == [41]));
+    }
+
+    #[test]
+    fn sashimi_x_wing() {
+        let board = board(&[(0, &[0, 4]), (4, &[0, 5])]);
+
+        let step = super::finned_x_wing(&board).unwrap();
+        assert_eq!(step.technique, Technique::SashimiXWing);
+        assert_eq!(step.eliminations, [(31, 1), (49, 1)]);
+    }
+}
@@ -0,0 +1,116 @@
+use super::{LogicalSolver, Step};
+use crate::error::SudokuError;
+use crate::sudoku::Sudoku;
+use std::fmt::Display;
+
+/// What to do next on a grid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Hint {
+    /// The simplest step from the current position.
+    Step(Step),
+    /// Every cell has been filled.
+    Solved,
+    /// None of the techniques the logical solver knows make progress.
+    Stuck,
+}
+
+impl Display for Hint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Step(step) => write!(f, "{step}"),
+            Self::Solved => f.write_str("suduko is already solved"),
+            Self::Stuck => f.write_str("no technique makes progress"),
+        }
+    }
+}
+
+/// Find the simplest step from the current position of `sudoku`, without
+/// changing it.
+///
+/// ```
+/// use std::str::FromStr;
+/// use sudoku_solver::logic::{next_hint, Hint};
+/// use sudoku_solver::StandardSudoku;
+///
+/// let game = StandardSudoku::from_str(
+///     "  9  2  5538 64  9162    3   3 27    546  1    7 1534 3  8 19 67  3  85  91   47 ",
+/// )
+/// .unwrap();
+///
+/// let Hint::Step(step) = next_hint(&game).unwrap() else {
+///     panic!("expected a step");
+/// };
+/// assert_eq!(step.placements.len(), 1);
+/// ```
+///
+/// Returns [`SudokuError::IllegalPosition`] if some of the digits on the grid
+/// clash, and [`SudokuError::Unsolvable`] if they don't clash but can't all be
+/// right, because there is no way to fill in the rest of the grid.
+pub fn next_hint(sudoku: &impl Sudoku) -> Result<Hint, SudokuError> {
+    let solver = LogicalSolver::new(sudoku)?;
+    if solver.solved() {
+        return Ok(Hint::Solved);
+    }
+
+    if !solver.unique() && sudoku.count_solutions(1) == 0 {
+        return Err(SudokuError::Unsolvable);
+    }
+
+    Ok(solver.next_step().map_or(Hint::Stuck, Hint::Step))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::Technique;
+    use crate::variants::StandardSudoku;
+    use std::str::FromStr;
+
+    const EASY: &str =
+        "  9  2  5538 64  9162    3   3 27    546  1    7 1534 3  8 19 67  3  85  91   47 ";
+
+    #[test]
+    fn next_hint() {
+        let game = StandardSudoku::from_str(EASY).unwrap();
+
+        let hint = super::next_hint(&game).unwrap();
+        assert!(matches!(&hint, Hint::Step(step) if step.technique == Technique::HiddenSingle));
+        assert_eq!(game.to_string(), EASY);
+
+        let mut solved = game;
+        solved.solve().unwrap();
+        assert_eq!(super::next_hint(&solved), Ok(Hint::Solved));
+        assert_eq!(
+            super::next_hint(&solved).unwrap().to_string(),
+            "suduko is already solved"
+        );
+    }
+
+    #[test]
+    fn mistakes() {
+        let game = StandardSudoku::from_str(EASY).unwrap();
+        let mut solved = game;
+        solved.solve().unwrap();
+
+        // A digit that doesn't clash with anything, but isn't the solution.
+        let mut wrong = game;
+        let (i, digit) = (0..81)
+            .filter(|&i| game.get(i).is_none())
+            .flat_map(|i| (1..=9).map(move |d| (i, d)))
+            .find(|&(i, d)| {
+                let mut game = game;
+                game.set(i, Some(d));
+                game.legal() && solved.get(i) != Some(d)
+            })
+            .unwrap();
+        wrong.set(i, Some(digit));
+        assert_eq!(super::next_hint(&wrong), Err(SudokuError::Unsolvable));
+
+        let mut clash = game;
+        clash.set(0, Some(9));
+        assert!(matches!(
+            super::next_hint(&clash),
+            Err(SudokuError::IllegalPosition { .. })
+        ));
+    }
+}
@@ -0,0 +1,98 @@
+use super::{Board, Step, Technique};
+use crate::solver::Candidates;
+
+/// Find a digit whose places in one house all lie inside a second house,
+/// which removes the digit from the rest of the second house.
+///
+/// When the first house is a subgrid this is a pointing pair, and when it is
+/// a row or column it is a box/line reduction. Rows and columns only ever
+/// share a single cell, so they are never paired with each other.
+pub(super) fn intersection(board: &Board) -> Option<Step> {
+    for pointing in [true, false] {
+        for (a, house) in board.houses().iter().enumerate() {
+            if house.is_line() == pointing {
+                continue;
+            }
+
+            for &b in house.cells.iter().flat_map(|&i| board.houses_of(i)) {
+                let other = &board.houses()[b];
+                if b == a || (house.is_line() && other.is_line()) {
+                    continue;
+                }
+
+                for digit in board.digits().iter() {
+                    let places = board.places(house, digit);
+                    if places.len() < 2 || !places.iter().all(|i| other.cells.contains(i)) {
+                        continue;
+                    }
+
+                    let rest = board.empty_in(other).filter(|i| !house.cells.contains(i));
+                    let eliminations = board.eliminations(rest, Candidates::single(digit));
+                    if !eliminations.is_empty() {
+                        let technique = if pointing {
+                            Technique::PointingPair
+                        } else {
+                            Technique::BoxLineReduction
+                        };
+
+                        return Some(Step {
+                            technique,
+                            houses: vec![house.kind, other.kind],
+                            cells: places,
+                            digits: vec![digit],
+                            placements: Vec::new(),
+                            eliminations,
+                            pattern: None,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::{GroupKind, Sudoku};
+    use crate::variants::StandardSudoku;
+
+    #[test]
+    fn pointing_pair() {
+        // The two lower rows of the first subgrid are full, so 1 has to go on
+        // the top row, and is removed from the rest of that row.
+        let mut game = StandardSudoku::new();
+        for (i, d) in [(9, 2), (10, 3), (11, 4), (18, 5), (19, 6), (20, 7)] {
+            game.set(i, Some(d));
+        }
+        let board = Board::new(&game);
+
+        let step = intersection(&board).unwrap();
+        assert_eq!(step.technique, Technique::PointingPair);
+        assert_eq!(step.houses, [GroupKind::Grid(0), GroupKind::Row(0)]);
+        assert_eq!(step.cells, [0, 1, 2]);
+        assert_eq!(
+            step.eliminations,
+            [(3, 1), (4, 1), (5, 1), (6, 1), (7, 1), (8, 1)]
+        );
+    }
+
+    #[test]
+    fn box_line_reduction() {
+        // 1 can only go in the first subgrid on the top row, so it is removed
+        // from the rest of that subgrid.
+        let mut game = StandardSudoku::new();
+        for (i, d) in [(3, 2), (4, 3), (5, 4), (6, 5), (7, 6), (8, 7)] {
+            game.set(i, Some(d));
+        }
+        let board = Board::new(&game);
+
+        let step = intersection(&board).unwrap();
+        assert_eq!(step.technique, Technique::BoxLineReduction);
+        assert_eq!(step.houses, [GroupKind::Row(0), GroupKind::Grid(0)]);
+        assert_eq!(step.digits, [1]);
+        assert_eq!(step.eliminations.len(), 6);
+    }
+}
@@ -0,0 +1,704 @@
+//! Solving Sudoku the way a person would.
+//!
+//! Instead of guessing, the logical solver keeps pencil marks of the digits
+//! that can still go in every cell, and repeatedly looks for a known technique
+//! that either places a digit or removes some of the pencil marks. Every step
+//! is recorded, along with the cells and digits that make it work, so that the
+//! reasoning can be followed by someone learning the game.
+//!
+//! The techniques are tried from simplest to hardest, so the steps are always
+//! the easiest way forward from the current position.
+
+mod chains;
+mod constraints;
+mod fish;
+mod hint;
+mod intersections;
+mod rating;
+mod singles;
+mod subsets;
+mod uniqueness;
+mod wings;
+
+pub use hint::{next_hint, Hint};
+pub use rating::{rate, Rating, Tier};
+
+use crate::constraint::Constraint;
+use crate::error::SudokuError;
+use crate::solver::Candidates;
+use crate::sudoku::{Cell, GroupKind, Sudoku};
+use std::fmt::Display;
+use std::sync::Arc;
+
+/// A technique for making progress on a Sudoku.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Technique {
+    /// Pencil marks ruled out by one of the constraints added on top of the
+    /// grid.
+    Constraint,
+    /// A digit that only has a single place left in a group.
+    HiddenSingle,
+    /// A cell that only has a single digit left.
+    NakedSingle,
+    /// A digit whose places in a subgrid all lie on the same row or column,
+    /// which removes it from the rest of that row or column.
+    PointingPair,
+    /// A digit whose places in a row or column all lie in the same subgrid,
+    /// which removes it from the rest of that subgrid.
+    BoxLineReduction,
+    /// Two cells in a group that only have the same two digits left.
+    NakedPair,
+    /// Two digits that only have the same two places left in a group.
+    HiddenPair,
+    /// Three cells in a group that only have the same three digits left.
+    NakedTriple,
+    /// Three digits that only have the same three places left in a group.
+    HiddenTriple,
+    /// Two rows where a digit can only go in the same two columns, or the
+    /// other way around.
+    XWing,
+    /// Three rows where a digit can only go in the same three columns, or the
+    /// other way around.
+    Swordfish,
+    /// Four rows where a digit can only go in the same four columns, or the
+    /// other way around.
+    Jellyfish,
+    /// An X-Wing with extra places for the digit, the fins, that all see each
+    /// other. The digit is only removed from cells that see every fin.
+    FinnedXWing,
+    /// A swordfish with extra places for the digit, the fins, that all see each
+    /// other. The digit is only removed from cells that see every fin.
+    FinnedSwordfish,
+    /// A jellyfish with extra places for the digit, the fins, that all see each
+    /// other. The digit is only removed from cells that see every fin.
+    FinnedJellyfish,
+    /// A finned X-Wing where one of the rows or columns has a single place
+    /// left once the fins are taken away.
+    SashimiXWing,
+    /// A finned swordfish where one of the rows or columns has a single place
+    /// left once the fins are taken away.
+    SashimiSwordfish,
+    /// A finned jellyfish where one of the rows or columns has a single place
+    /// left once the fins are taken away.
+    SashimiJellyfish,
+    /// A cell with two digits that sees two cells which each share one of
+    /// those digits and have a third digit in common.
+    XYWing,
+    /// An XY-Wing where the middle cell also has the third digit.
+    XYZWing,
+    /// Two cells with the same two digits, joined by a row, column or
+    /// subgrid where one of the digits only has two places left.
+    WWing,
+    /// Two colors given to the places of a digit that are linked through
+    /// groups where the digit only has two places left.
+    SimpleColoring,
+    /// A chain of strong and weak links between the places of a single digit.
+    XChain,
+    /// A chain of strong and weak links between any digits and cells.
+    AlternatingInferenceChain,
+    /// A rectangle where three corners only have the same two digits left,
+    /// which removes them from the last corner.
+    UniqueRectangle1,
+    /// A rectangle where two corners only have the same two digits left, and
+    /// the other two share a single extra digit.
+    UniqueRectangle2,
+    /// A rectangle where the extra digits of two corners form a naked subset
+    /// with other cells in their group.
+    UniqueRectangle3,
+    /// A rectangle where one of its two digits only has the two extra
+    /// corners left in a group.
+    UniqueRectangle4,
+    /// A grid where every empty cell has two digits left but one, which has
+    /// three.
+    BivalueUniversalGrave,
+}
+
+impl Display for Technique {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Constraint => "constraint",
+            Self::HiddenSingle => "hidden single",
+            Self::NakedSingle => "naked single",
+            Self::PointingPair => "pointing pair",
+            Self::BoxLineReduction => "box/line reduction",
+            Self::NakedPair => "naked pair",
+            Self::HiddenPair => "hidden pair",
+            Self::NakedTriple => "naked triple",
+            Self::HiddenTriple => "hidden triple",
+            Self::XWing => "X-Wing",
+            Self::Swordfish => "swordfish",
+            Self::Jellyfish => "jellyfish",
+            Self::FinnedXWing => "finned X-Wing",
+            Self::FinnedSwordfish => "finned swordfish",
+            Self::FinnedJellyfish => "finned jellyfish",
+            Self::SashimiXWing => "sashimi X-Wing",
+            Self::SashimiSwordfish => "sashimi swordfish",
+            Self::SashimiJellyfish => "sashimi jellyfish",
+            Self::XYWing => "XY-Wing",
+            Self::XYZWing => "XYZ-Wing",
+            Self::WWing => "W-Wing",
+            Self::SimpleColoring => "simple coloring",
+            Self::XChain => "X-Chain",
+            Self::AlternatingInferenceChain => "alternating inference chain",
+            Self::UniqueRectangle1 => "unique rectangle type 1",
+            Self::UniqueRectangle2 => "unique rectangle type 2",
+            Self::UniqueRectangle3 => "unique rectangle type 3",
+            Self::UniqueRectangle4 => "unique rectangle type 4",
+            Self::BivalueUniversalGrave => "BUG+1",
+        })
+    }
+}
+
+/// How two candidates next to each other in a chain are linked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LinkKind {
+    /// At least one of the candidates is true.
+    Strong,
+    /// At most one of the candidates is true.
+    Weak,
+}
+
+/// A candidate in a chain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ChainNode {
+    /// The cell of the candidate.
+    pub cell: usize,
+    /// The digit of the candidate.
+    pub digit: u8,
+    /// How the candidate is linked to the one before it in the chain, or
+    /// [`None`] for the first candidate.
+    pub link: Option<LinkKind>,
+}
+
+/// The structure of a step, for techniques that need more than a list of
+/// cells and groups to be explained.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Pattern {
+    /// Every place of a digit in the base groups lies in one of the cover
+    /// groups, apart from the fins.
+    Fish {
+        /// The rows or columns the digit is confined in.
+        base: Vec<GroupKind>,
+        /// The columns or rows covering every place of the digit in the base.
+        cover: Vec<GroupKind>,
+        /// The places of the digit in the base that are not covered.
+        fins: Vec<usize>,
+    },
+    /// A chain of candidates, where at least one of the two ends is true.
+    Chain(Vec<ChainNode>),
+}
+
+/// A single deduction made by the logical solver.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    /// The technique used.
+    pub technique: Technique,
+    /// The groups the technique looks at.
+    pub houses: Vec<GroupKind>,
+    /// The cells that make up the pattern.
+    pub cells: Vec<usize>,
+    /// The digits that make up the pattern.
+    pub digits: Vec<u8>,
+    /// The digits placed by the step, as `(cell, digit)`.
+    pub placements: Vec<(usize, u8)>,
+    /// The pencil marks removed by the step, as `(cell, digit)`.
+    pub eliminations: Vec<(usize, u8)>,
+    /// The structure of the step, if it has any beyond its cells and groups.
+    pub pattern: Option<Pattern>,
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.technique)?;
+
+        let digits = self.digits.iter().map(u8::to_string).collect::<Vec<_>>();
+        write!(f, " {{{}}}", digits.join(", "))?;
+
+        let cells = self.cells.iter().map(usize::to_string).collect::<Vec<_>>();
+        write!(f, " on cells {}", cells.join(", "))?;
+
+        for (i, house) in self.houses.iter().enumerate() {
+            write!(f, "{}{house}", if i == 0 { " in " } else { " and " })?;
+        }
+
+        match &self.pattern {
+            Some(Pattern::Fish { fins, .. }) if !fins.is_empty() => {
+                let fins = fins.iter().map(usize::to_string).collect::<Vec<_>>();
+                write!(f, " with fins on cells {}", fins.join(", "))?;
+            }
+            Some(Pattern::Chain(chain)) => {
+                f.write_str(" along ")?;
+                for node in chain {
+                    match node.link {
+                        Some(LinkKind::Strong) => f.write_str(" = ")?,
+                        Some(LinkKind::Weak) => f.write_str(" - ")?,
+                        None => (),
+                    }
+                    write!(f, "{}@{}", node.digit, node.cell)?;
+                }
+            }
+            _ => (),
+        }
+
+        for (i, (cell, digit)) in self.placements.iter().enumerate() {
+            let separator = if i == 0 { ": place " } else { ", " };
+            write!(f, "{separator}{digit} on cell {cell}")?;
+        }
+
+        for (i, (cell, digit)) in self.eliminations.iter().enumerate() {
+            let separator = if i == 0 { ": remove " } else { ", " };
+            write!(f, "{separator}{digit} from cell {cell}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A group of cells that must contain every digit exactly once.
+pub(crate) struct House {
+    pub kind: GroupKind,
+    pub cells: Vec<usize>,
+}
+
+impl House {
+    /// Check if the house is a row or a column.
+    pub fn is_line(&self) -> bool {
+        matches!(self.kind, GroupKind::Row(_) | GroupKind::Column(_))
+    }
+}
+
+/// A grid along with the pencil marks of every cell.
+pub(crate) struct Board {
+    cells: Vec<Cell>,
+    candidates: Vec<Candidates>,
+    houses: Vec<House>,
+    houses_of: Vec<Vec<usize>>,
+    peers: Vec<Vec<usize>>,
+    constraints: Vec<Arc<dyn Constraint>>,
+    digits: Candidates,
+    unique: bool,
+}
+
+impl Board {
+    fn new(sudoku: &impl Sudoku) -> Self {
+        let len = sudoku.cells().len();
+
+        let houses = sudoku
+            .group_indices()
+            .into_iter()
+            .enumerate()
+            .map(|(group, cells)| House {
+                kind: sudoku.group_kind(group),
+                cells,
+            })
+            .collect::<Vec<_>>();
+
+        let mut houses_of = vec![Vec::new(); len];
+        let mut peers = vec![Vec::new(); len];
+        for (h, house) in houses.iter().enumerate() {
+            for &i in &house.cells {
+                houses_of[i].push(h);
+                peers[i].extend(house.cells.iter().copied().filter(|&j| j != i));
+            }
+        }
+
+        // Cages don't have every digit, so they aren't houses, but their
+        // cells still can't repeat a digit.
+        for cage in sudoku.cages() {
+            for &i in &cage.cells {
+                peers[i].extend(cage.cells.iter().copied().filter(|&j| j != i));
+            }
+        }
+
+        for p in &mut peers {
+            p.sort_unstable();
+            p.dedup();
+        }
+
+        let digits = Candidates::from_range(sudoku.cell_values());
+        let mut board = Self {
+            cells: vec![None; len],
+            candidates: vec![digits; len],
+            houses,
+            houses_of,
+            peers,
+            constraints: sudoku.constraints(),
+            digits,
+            unique: false,
+        };
+
+        for (i, cell) in sudoku.cells().iter().enumerate() {
+            if let Some(digit) = *cell {
+                board.place(i, digit);
+            }
+        }
+
+        board
+    }
+
+    /// Get the digit on a cell.
+    pub fn cell(&self, i: usize) -> Cell {
+        self.cells[i]
+    }
+
+    /// Get the pencil marks of a cell.
+    pub fn candidates(&self, i: usize) -> Candidates {
+        self.candidates[i]
+    }
+
+    /// Get every house on the board.
+    pub fn houses(&self) -> &[House] {
+        &self.houses
+    }
+
+    /// Get the indices of every house containing cell `i`.
+    pub fn houses_of(&self, i: usize) -> &[usize] {
+        &self.houses_of[i]
+    }
+
+    /// Get every cell that shares a house with cell `i`.
+    pub fn peers(&self, i: usize) -> &[usize] {
+        &self.peers[i]
+    }
+
+    /// Check if cells `a` and `b` share a house.
+    pub fn sees(&self, a: usize, b: usize) -> bool {
+        self.peers[a].binary_search(&b).is_ok()
+    }
+
+    /// Get the empty cells of a house where `digit` can still be placed.
+    pub fn places(&self, house: &House, digit: u8) -> Vec<usize> {
+        house
+            .cells
+            .iter()
+            .copied()
+            .filter(|&i| self.cells[i].is_none() && self.candidates[i].contains(digit))
+            .collect()
+    }
+
+    /// Get the constraints added on top of the grid.
+    pub fn constraints(&self) -> &[Arc<dyn Constraint>] {
+        &self.constraints
+    }
+
+    /// Get every digit that can be placed on the board.
+    pub fn digits(&self) -> Candidates {
+        self.digits
+    }
+
+    /// Check if the puzzle is known to have a single solution, which allows
+    /// the techniques that rule out a second one.
+    pub fn unique(&self) -> bool {
+        self.unique
+    }
+
+    /// Get every empty cell on the board.
+    pub fn empty_cells(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.cells.len()).filter(|&i| self.cells[i].is_none())
+    }
+
+    /// Get the empty cells of a house.
+    pub fn empty_in<'a>(&'a self, house: &'a House) -> impl Iterator<Item = usize> + 'a {
+        house
+            .cells
+            .iter()
+            .copied()
+            .filter(|&i| self.cells[i].is_none())
+    }
+
+    /// Get the pencil marks out of `digits` that are left on the empty cells
+    /// of `cells`.
+    pub fn eliminations(
+        &self,
+        cells: impl IntoIterator<Item = usize>,
+        digits: Candidates,
+    ) -> Vec<(usize, u8)> {
+        cells
+            .into_iter()
+            .filter(|&i| self.cells[i].is_none())
+            .flat_map(|i| {
+                self.candidates[i]
+                    .intersection(digits)
+                    .iter()
+                    .map(move |d| (i, d))
+            })
+            .collect()
+    }
+
+    fn place(&mut self, i: usize, digit: u8) {
+        self.cells[i] = Some(digit);
+        self.candidates[i] = Candidates::single(digit);
+
+        for n in 0..self.peers[i].len() {
+            let p = self.peers[i][n];
+            if self.cells[p].is_none() {
+                self.candidates[p].remove(digit);
+            }
+        }
+    }
+
+    fn apply(&mut self, step: &Step) {
+        for &(i, digit) in &step.placements {
+            self.place(i, digit);
+        }
+
+        for &(i, digit) in &step.eliminations {
+            self.candidates[i].remove(digit);
+        }
+    }
+
+    fn filled(&self) -> bool {
+        self.cells.iter().all(Option::is_some)
+    }
+
+    fn broken(&self) -> bool {
+        self.empty_cells().any(|i| self.candidates[i].is_empty())
+    }
+}
+
+type Strategy = fn(&Board) -> Option<Step>;
+
+/// Every strategy, from simplest to hardest.
+const STRATEGIES: &[Strategy] = &[
+    // The constraints go first, as the other strategies expect the pencil
+    // marks to already follow every rule of the grid.
+    constraints::constraint,
+    singles::hidden_single,
+    singles::naked_single,
+    intersections::intersection,
+    subsets::naked_pair,
+    fish::x_wing,
+    subsets::hidden_pair,
+    subsets::naked_triple,
+    fish::swordfish,
+    subsets::hidden_triple,
+    wings::xy_wing,
+    wings::xyz_wing,
+    wings::w_wing,
+    uniqueness::unique_rectangle_1,
+    uniqueness::unique_rectangle_2,
+    uniqueness::unique_rectangle_4,
+    uniqueness::unique_rectangle_3,
+    fish::finned_x_wing,
+    fish::finned_swordfish,
+    fish::jellyfish,
+    fish::finned_jellyfish,
+    uniqueness::bug_plus_one,
+    chains::simple_coloring,
+    chains::x_chain,
+    chains::alternating_inference_chain,
+];
+
+/// A solver that only uses logical techniques.
+///
+/// ```
+/// use std::str::FromStr;
+/// use sudoku_solver::logic::LogicalSolver;
+/// use sudoku_solver::StandardSudoku;
+///
+/// let game = StandardSudoku::from_str(
+///     "7 2 519  3 492 1      7 65 931      2    738 67 34  1949768 2 11   3         94 7",
+/// )
+/// .unwrap();
+///
+/// let mut solver = LogicalSolver::new(&game).unwrap();
+/// let steps = solver.solve();
+/// assert!(solver.solved());
+/// assert!(!steps.is_empty());
+/// ```
+pub struct LogicalSolver {
+    board: Board,
+}
+
+impl LogicalSolver {
+    /// Start solving `sudoku`.
+    ///
+    /// Techniques that rely on the puzzle having a single solution, like
+    /// unique rectangles, are only used once that has been checked, and never
+    /// on grids with cages or constraints, which can tell the two digits of a
+    /// rectangle apart.
+    ///
+    /// Returns [`SudokuError::IllegalPosition`] if the grid already breaks the
+    /// rules.
+    pub fn new(sudoku: &impl Sudoku) -> Result<Self, SudokuError> {
+        let conflicts = sudoku.conflicts();
+        if !conflicts.is_empty() {
+            return Err(SudokuError::IllegalPosition { conflicts });
+        }
+
+        let mut board = Board::new(sudoku);
+        board.unique = sudoku.cages().is_empty()
+            && sudoku.constraints().is_empty()
+            && sudoku.has_unique_solution();
+        Ok(Self { board })
+    }
+
+    /// Find the simplest step from the current position, without applying it.
+    pub fn next_step(&self) -> Option<Step> {
+        if self.board.broken() {
+            return None;
+        }
+
+        STRATEGIES.iter().find_map(|strategy| strategy(&self.board))
+    }
+
+    /// Find the simplest step from the current position and apply it.
+    pub fn step(&mut self) -> Option<Step> {
+        let step = self.next_step()?;
+        self.board.apply(&step);
+        Some(step)
+    }
+
+    /// Apply steps until the grid is solved or no technique makes progress,
+    /// returning every step taken.
+    pub fn solve(&mut self) -> Vec<Step> {
+        std::iter::from_fn(|| self.step()).collect()
+    }
+
+    /// Check if the uniqueness techniques are in use, which needs the puzzle to
+    /// have been found to have a single solution.
+    pub fn unique(&self) -> bool {
+        self.board.unique()
+    }
+
+    /// Check if every cell has been filled.
+    pub fn solved(&self) -> bool {
+        self.board.filled()
+    }
+
+    /// Get the cells of the grid in its current state.
+    pub fn cells(&self) -> &[Cell] {
+        &self.board.cells
+    }
+
+    /// Get the pencil marks of cell `i`.
+    pub fn candidates(&self, i: usize) -> Vec<u8> {
+        self.board.candidates(i).iter().collect()
+    }
+}
+
+/// Get every way to pick `n` items from `items`, keeping their order.
+pub(crate) fn combinations<T: Copy>(items: &[T], n: usize) -> Vec<Vec<T>> {
+    if n == 0 {
+        return vec![Vec::new()];
+    }
+
+    (0..items.len())
+        .flat_map(|i| {
+            combinations(&items[i + 1..], n - 1)
+                .into_iter()
+                .map(move |mut rest| {
+                    rest.insert(0, items[i]);
+                    rest
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variants::{HyperSudoku, StandardSudoku};
+    use std::str::FromStr;
+
+    #[test]
+    fn combinations() {
+        assert_eq!(
+            super::combinations(&[1, 2, 3, 4], 2),
+            [[1, 2], [1, 3], [1, 4], [2, 3], [2, 4], [3, 4]]
+        );
+        assert_eq!(super::combinations(&[1, 2], 3), Vec::<Vec<i32>>::new());
+    }
+
+    #[test]
+    fn solve() {
+        let game = StandardSudoku::from_str(
+            "1 6    7448 6 1      94   6     923      3   329716   8       367 3   9  35 64  2",
+        )
+        .unwrap();
+
+        let mut solver = LogicalSolver::new(&game).unwrap();
+        let steps = solver.solve();
+        assert!(solver.solved());
+
+        let mut solved = game;
+        solved.solve().unwrap();
+        assert_eq!(solver.cells(), solved.cells());
+
+        assert_eq!(
+            steps.iter().map(|s| s.placements.len()).sum::<usize>(),
+            game.cells().iter().filter(|c| c.is_none()).count()
+        );
+    }
+
+    #[test]
+    fn hyper_windows() {
+        // Every cell but the last of the first window is filled, so 9 has to go
+        // on the last cell.
+        let mut game = HyperSudoku::new();
+        for (i, d) in [
+            (10, 1),
+            (11, 2),
+            (12, 3),
+            (19, 4),
+            (20, 5),
+            (21, 6),
+            (28, 7),
+            (29, 8),
+        ] {
+            game.set(i, Some(d));
+        }
+
+        let step = LogicalSolver::new(&game).unwrap().next_step().unwrap();
+        assert_eq!(step.technique, Technique::HiddenSingle);
+        assert_eq!(step.houses, [GroupKind::Window(0)]);
+        assert_eq!(step.placements, [(30, 9)]);
+        assert_eq!(
+            step.to_string(),
+            "hidden single {9} on cells 30 in window 0: place 9 on cell 30"
+        );
+
+        let game = HyperSudoku::from_str(
+            "       1   2    34    51        65   7 3   8   3          8    58    9  69       ",
+        )
+        .unwrap();
+        let mut solver = LogicalSolver::new(&game).unwrap();
+        solver.solve();
+        assert!(solver.solved());
+    }
+
+    #[test]
+    fn uniqueness() {
+        let game = StandardSudoku::from_str(
+            "  9  46    8    513   7   4   4  3 2 1 86  7      9      9 6    9       6 1  3   ",
+        )
+        .unwrap();
+        let mut solver = LogicalSolver::new(&game).unwrap();
+        assert!(solver.unique());
+
+        let steps = solver.solve();
+        assert!(solver.solved());
+        assert!(steps
+            .iter()
+            .any(|step| step.technique == Technique::BivalueUniversalGrave
+                && step.placements == [(52, 8)]));
+
+        // Two of the cells in each of the first two rows can swap 4 and 9.
+        let game = StandardSudoku::from_str(
+            "76285194335  2617881  73652931568724245197386678342519497685231126734895583219467",
+        )
+        .unwrap();
+        let mut solver = LogicalSolver::new(&game).unwrap();
+        assert!(!solver.unique());
+        assert!(solver.solve().is_empty());
+    }
+
+    #[test]
+    fn illegal() {
+        let game = StandardSudoku::from_str(
+            "11                                                                               ",
+        )
+        .unwrap();
+        assert!(LogicalSolver::new(&game).is_err());
+    }
+}
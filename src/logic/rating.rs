@@ -0,0 +1,265 @@
+use super::{LogicalSolver, Step, Technique};
+use crate::error::SudokuError;
+use crate::sudoku::{GroupKind, Sudoku};
+use std::fmt::Display;
+use std::ops::Range;
+
+/// The score given to puzzles the logical solver cannot finish.
+const UNSOLVED: f32 = 10.0;
+
+/// How much each step beyond singles adds to the score, and the most those
+/// steps can add together.
+const STEP_BONUS: f32 = 0.1;
+const MAX_BONUS: f32 = 1.0;
+
+impl Technique {
+    /// Get the difficulty of the technique, roughly on the Sudoku Explainer
+    /// scale.
+    ///
+    /// Hidden singles are rated as if they were found in a row or column; see
+    /// [`Step::difficulty`] for singles in a subgrid.
+    pub fn difficulty(&self) -> f32 {
+        match self {
+            Self::Constraint => 1.0,
+            Self::HiddenSingle => 1.5,
+            Self::NakedSingle => 2.3,
+            Self::PointingPair => 2.6,
+            Self::BoxLineReduction => 2.8,
+            Self::NakedPair => 3.0,
+            Self::XWing => 3.2,
+            Self::HiddenPair => 3.4,
+            Self::NakedTriple => 3.6,
+            Self::Swordfish => 3.8,
+            Self::HiddenTriple => 4.0,
+            Self::XYWing => 4.2,
+            Self::XYZWing | Self::WWing => 4.4,
+            Self::UniqueRectangle1 | Self::UniqueRectangle4 => 4.5,
+            Self::UniqueRectangle2 | Self::UniqueRectangle3 => 4.6,
+            Self::FinnedXWing => 4.6,
+            Self::SashimiXWing => 4.7,
+            Self::FinnedSwordfish => 4.8,
+            Self::SashimiSwordfish => 4.9,
+            Self::Jellyfish => 5.2,
+            Self::FinnedJellyfish => 5.4,
+            Self::SashimiJellyfish => 5.5,
+            Self::BivalueUniversalGrave => 5.6,
+            Self::SimpleColoring => 6.2,
+            Self::XChain => 6.6,
+            Self::AlternatingInferenceChain => 7.0,
+        }
+    }
+}
+
+impl Step {
+    /// Get the difficulty of the step, roughly on the Sudoku Explainer scale.
+    ///
+    /// This is the difficulty of its technique, apart from hidden singles in a
+    /// subgrid, window or region, which are easier to spot than in a row or
+    /// column.
+    pub fn difficulty(&self) -> f32 {
+        match (self.technique, &self.houses[..]) {
+            (Technique::HiddenSingle, [kind])
+                if !matches!(kind, GroupKind::Row(_) | GroupKind::Column(_)) =>
+            {
+                1.2
+            }
+            (technique, _) => technique.difficulty(),
+        }
+    }
+}
+
+/// A named band of difficulty.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Tier {
+    /// Only needs hidden singles in subgrids.
+    Easy,
+    /// Only needs singles.
+    Medium,
+    /// Needs intersections, subsets, basic fish or wings.
+    Hard,
+    /// Needs uniqueness, finned fish or chains, a long run of hard steps, or
+    /// more than the logical solver knows.
+    Evil,
+}
+
+impl Tier {
+    /// Every tier, from easiest to hardest.
+    const ALL: [Self; 4] = [Self::Easy, Self::Medium, Self::Hard, Self::Evil];
+
+    fn from_score(score: f32) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|tier| score < tier.scores().end)
+            .unwrap_or(Self::Evil)
+    }
+
+    /// Get the range of scores that fall in the tier.
+    pub fn scores(&self) -> Range<f32> {
+        match self {
+            Self::Easy => 0.0..1.5,
+            Self::Medium => 1.5..2.5,
+            Self::Hard => 2.5..4.5,
+            Self::Evil => 4.5..f32::INFINITY,
+        }
+    }
+}
+
+impl Display for Tier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Easy => "easy",
+            Self::Medium => "medium",
+            Self::Hard => "hard",
+            Self::Evil => "evil",
+        })
+    }
+}
+
+/// How hard a puzzle is to solve by logic alone.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rating {
+    /// The difficulty of the hardest step, plus a tenth for every step that
+    /// needs more than a single, up to a whole point.
+    pub score: f32,
+    /// The band the score falls in.
+    pub tier: Tier,
+    /// The technique of the hardest step, or [`None`] if no steps were needed.
+    pub hardest: Option<Technique>,
+    /// The number of steps taken.
+    pub steps: usize,
+    /// Whether the logical solver finished the puzzle. If not, the score is
+    /// set to 10.
+    pub solved: bool,
+}
+
+impl Display for Rating {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.1} ({})", self.score, self.tier)
+    }
+}
+
+/// Rate how hard `sudoku` is by solving it with the [`LogicalSolver`].
+///
+/// ```
+/// use std::str::FromStr;
+/// use sudoku_solver::logic::{rate, Tier};
+/// use sudoku_solver::StandardSudoku;
+///
+/// let game = StandardSudoku::from_str(
+///     "  9  2  5538 64  9162    3   3 27    546  1    7 1534 3  8 19 67  3  85  91   47 ",
+/// )
+/// .unwrap();
+///
+/// let rating = rate(&game).unwrap();
+/// assert_eq!(rating.tier, Tier::Easy);
+/// ```
+///
+/// Returns [`SudokuError::IllegalPosition`] if the grid already breaks the
+/// rules.
+pub fn rate(sudoku: &impl Sudoku) -> Result<Rating, SudokuError> {
+    let mut solver = LogicalSolver::new(sudoku)?;
+    let steps = solver.solve();
+
+    let hardest = steps
+        .iter()
+        .max_by(|a, b| a.difficulty().total_cmp(&b.difficulty()));
+    let hard_steps = steps
+        .iter()
+        .filter(|step| {
+            !matches!(
+                step.technique,
+                Technique::Constraint | Technique::HiddenSingle | Technique::NakedSingle
+            )
+        })
+        .count();
+
+    let score = if solver.solved() {
+        let bonus = (hard_steps as f32 * STEP_BONUS).min(MAX_BONUS);
+        hardest.map_or(0.0, Step::difficulty) + bonus
+    } else {
+        UNSOLVED
+    };
+
+    Ok(Rating {
+        score,
+        tier: Tier::from_score(score),
+        hardest: hardest.map(|step| step.technique),
+        steps: steps.len(),
+        solved: solver.solved(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variants::{HyperSudoku, StandardSudoku};
+    use std::str::FromStr;
+
+    fn rating(s: &str) -> Rating {
+        rate(&StandardSudoku::from_str(s).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn benchmarks() {
+        let easy = rating(
+            "  9  2  5538 64  9162    3   3 27    546  1    7 1534 3  8 19 67  3  85  91   47 ",
+        );
+        assert_eq!(easy.tier, Tier::Easy);
+        assert_eq!(easy.hardest, Some(Technique::HiddenSingle));
+        assert_eq!(easy.score, 1.2);
+
+        let medium = rating(
+            "1 6    7448 6 1      94   6     923      3   329716   8       367 3   9  35 64  2",
+        );
+        assert_eq!(medium.tier, Tier::Medium);
+        assert!(Tier::Medium.scores().contains(&medium.score));
+
+        let hard = rating(
+            "   75       2 8 45 5      2 7   1   8 1 492 7  9 2 6  41      8  3   4    8  456 ",
+        );
+        assert_eq!(hard.tier, Tier::Hard);
+        assert_eq!(hard.hardest, Some(Technique::PointingPair));
+        assert!(hard.score > 2.6);
+
+        let evil = rating(
+            "  3  7  2  15  79  9      4        9 1   436   5 8    3  4           2   6   317 ",
+        );
+        // Fewer clues don't make for harder steps: the evil benchmark only
+        // needs a pointing pair, like the hard one.
+        assert_eq!(evil.tier, Tier::Hard);
+        assert_eq!(evil.hardest, Some(Technique::PointingPair));
+        assert!(evil.solved);
+    }
+
+    #[test]
+    fn tiers() {
+        for tier in Tier::ALL {
+            let scores = tier.scores();
+            assert_eq!(Tier::from_score(scores.start), tier);
+            assert_eq!(Tier::from_score(scores.end - 0.01), tier);
+        }
+    }
+
+    #[test]
+    fn unsolved() {
+        // AI Escargot needs more than the logical solver knows.
+        let rating = rating(
+            "1    7 9  3  2   8  96  5    53  9   1  8   26    4   3      1  4      7  7   3  ",
+        );
+        assert!(!rating.solved);
+        assert_eq!(rating.score, 10.0);
+        assert_eq!(rating.tier, Tier::Evil);
+        assert_eq!(rating.to_string(), "10.0 (evil)");
+    }
+
+    #[test]
+    fn variants() {
+        let game = HyperSudoku::from_str(
+            "       1   2    34    51        65   7 3   8   3          8    58    9  69       ",
+        )
+        .unwrap();
+        let rating = rate(&game).unwrap();
+        assert!(rating.solved);
+        assert!(rating.steps > 0);
+    }
+}
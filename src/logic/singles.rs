@@ -0,0 +1,73 @@
+use super::{Board, House, Step, Technique};
+
+/// Find a digit that only has a single place left in a house.
+///
+/// Subgrids and other houses are searched before rows and columns, as those
+/// singles are the easiest to spot.
+pub(super) fn hidden_single(board: &Board) -> Option<Step> {
+    let (lines, others): (Vec<&House>, Vec<&House>) =
+        board.houses().iter().partition(|house| house.is_line());
+
+    others.into_iter().chain(lines).find_map(|house| {
+        board
+            .digits()
+            .iter()
+            .find_map(|digit| match board.places(house, digit)[..] {
+                [i] => Some(Step {
+                    technique: Technique::HiddenSingle,
+                    houses: vec![house.kind],
+                    cells: vec![i],
+                    digits: vec![digit],
+                    placements: vec![(i, digit)],
+                    eliminations: Vec::new(),
+                    pattern: None,
+                }),
+                _ => None,
+            })
+    })
+}
+
+/// Find a cell that only has a single digit left.
+pub(super) fn naked_single(board: &Board) -> Option<Step> {
+    board
+        .empty_cells()
+        .filter(|&i| board.candidates(i).len() == 1)
+        .map(|i| {
+            let digit = board.candidates(i).first().unwrap();
+            Step {
+                technique: Technique::NakedSingle,
+                houses: Vec::new(),
+                cells: vec![i],
+                digits: vec![digit],
+                placements: vec![(i, digit)],
+                eliminations: Vec::new(),
+                pattern: None,
+            }
+        })
+        .next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::GroupKind;
+    use crate::variants::StandardSudoku;
+    use std::str::FromStr;
+
+    #[test]
+    fn singles() {
+        let game = StandardSudoku::from_str(
+            "12345678                                                                         ",
+        )
+        .unwrap();
+        let board = Board::new(&game);
+
+        let step = hidden_single(&board).unwrap();
+        assert_eq!(step.houses, [GroupKind::Row(0)]);
+        assert_eq!(step.placements, [(8, 9)]);
+
+        let step = naked_single(&board).unwrap();
+        assert_eq!(step.placements, [(8, 9)]);
+        assert_eq!(step.houses, []);
+    }
+}
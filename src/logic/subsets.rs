@@ -0,0 +1,146 @@
+use super::{combinations, Board, Step, Technique};
+use crate::solver::Candidates;
+
+pub(super) fn naked_pair(board: &Board) -> Option<Step> {
+    naked_subset(board, 2, Technique::NakedPair)
+}
+
+pub(super) fn naked_triple(board: &Board) -> Option<Step> {
+    naked_subset(board, 3, Technique::NakedTriple)
+}
+
+pub(super) fn hidden_pair(board: &Board) -> Option<Step> {
+    hidden_subset(board, 2, Technique::HiddenPair)
+}
+
+pub(super) fn hidden_triple(board: &Board) -> Option<Step> {
+    hidden_subset(board, 3, Technique::HiddenTriple)
+}
+
+/// Find `n` cells in a house that only have the same `n` digits left between
+/// them, which removes those digits from every other cell in the house.
+fn naked_subset(board: &Board, n: usize, technique: Technique) -> Option<Step> {
+    for house in board.houses() {
+        let cells = board
+            .empty_in(house)
+            .filter(|&i| (2..=n as u32).contains(&board.candidates(i).len()))
+            .collect::<Vec<_>>();
+
+        for subset in combinations(&cells, n) {
+            let digits = subset.iter().fold(Candidates::default(), |set, &i| {
+                set.union(board.candidates(i))
+            });
+            if digits.len() != n as u32 {
+                continue;
+            }
+
+            let others = board.empty_in(house).filter(|i| !subset.contains(i));
+            let eliminations = board.eliminations(others, digits);
+            if !eliminations.is_empty() {
+                return Some(Step {
+                    technique,
+                    houses: vec![house.kind],
+                    cells: subset,
+                    digits: digits.iter().collect(),
+                    placements: Vec::new(),
+                    eliminations,
+                    pattern: None,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// Find `n` digits that only have the same `n` places left in a house, which
+/// removes every other digit from those places.
+fn hidden_subset(board: &Board, n: usize, technique: Technique) -> Option<Step> {
+    for house in board.houses() {
+        let digits = board
+            .digits()
+            .iter()
+            .filter(|&d| (2..=n).contains(&board.places(house, d).len()))
+            .collect::<Vec<_>>();
+
+        for subset in combinations(&digits, n) {
+            let mut cells = subset
+                .iter()
+                .flat_map(|&d| board.places(house, d))
+                .collect::<Vec<_>>();
+            cells.sort_unstable();
+            cells.dedup();
+            if cells.len() != n {
+                continue;
+            }
+
+            let digits = Candidates::from_digits(subset.iter().copied());
+            let eliminations =
+                board.eliminations(cells.iter().copied(), board.digits().difference(digits));
+            if !eliminations.is_empty() {
+                return Some(Step {
+                    technique,
+                    houses: vec![house.kind],
+                    cells,
+                    digits: subset,
+                    placements: Vec::new(),
+                    eliminations,
+                    pattern: None,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::{GroupKind, Sudoku};
+    use crate::variants::StandardSudoku;
+
+    #[test]
+    fn naked_pair() {
+        // The first row is missing 1, 2, 3 and 4, and column 0 and 1 both
+        // have 3 and 4, leaving only 1 and 2 for the first two cells.
+        let mut game = StandardSudoku::new();
+        for (i, d) in [
+            (4, 5),
+            (5, 6),
+            (6, 7),
+            (7, 8),
+            (8, 9),
+            (18, 3),
+            (36, 4),
+            (28, 3),
+            (46, 4),
+        ] {
+            game.set(i, Some(d));
+        }
+        let board = Board::new(&game);
+
+        let step = super::naked_pair(&board).unwrap();
+        assert_eq!(step.houses, [GroupKind::Row(0)]);
+        assert_eq!(step.cells, [0, 1]);
+        assert_eq!(step.digits, [1, 2]);
+        assert_eq!(step.eliminations, [(2, 1), (2, 2), (3, 1), (3, 2)]);
+    }
+
+    #[test]
+    fn hidden_pair() {
+        // 1 and 2 are blocked from every cell of the first row but the first
+        // two.
+        let mut game = StandardSudoku::new();
+        for (i, d) in [(12, 1), (24, 1), (38, 1), (21, 2), (15, 2), (47, 2)] {
+            game.set(i, Some(d));
+        }
+        let board = Board::new(&game);
+
+        let step = super::hidden_pair(&board).unwrap();
+        assert_eq!(step.houses, [GroupKind::Row(0)]);
+        assert_eq!(step.cells, [0, 1]);
+        assert_eq!(step.digits, [1, 2]);
+        assert_eq!(step.eliminations.len(), 14);
+    }
+}
@@ -0,0 +1,447 @@
+use super::{combinations, Board, GroupKind, Step, Technique};
+use crate::solver::Candidates;
+
+/// Four empty cells on the corners of a rectangle, as `[top left, top right,
+/// bottom left, bottom right]`.
+type Rectangle = [usize; 4];
+
+/// Get every rectangle whose corners could swap two digits without breaking
+/// any house, so that a grid where all four corners only have those digits
+/// left would have two solutions.
+///
+/// Every house that touches the rectangle has to hold exactly two corners
+/// next to each other, which leaves out rectangles inside a single subgrid,
+/// and on variants with extra houses the ones that cut through them.
+fn rectangles(board: &Board) -> Vec<Rectangle> {
+    let rows = board
+        .houses()
+        .iter()
+        .filter(|house| matches!(house.kind, GroupKind::Row(_)))
+        .collect::<Vec<_>>();
+
+    let mut rectangles = Vec::new();
+    for (n, top) in rows.iter().enumerate() {
+        for bottom in &rows[n + 1..] {
+            for left in 0..top.cells.len() {
+                for right in left + 1..top.cells.len() {
+                    let corners = [
+                        top.cells[left],
+                        top.cells[right],
+                        bottom.cells[left],
+                        bottom.cells[right],
+                    ];
+                    if corners.iter().all(|&i| board.cell(i).is_none()) && deadly(board, corners) {
+                        rectangles.push(corners);
+                    }
+                }
+            }
+        }
+    }
+
+    rectangles
+}
+
+fn deadly(board: &Board, corners: Rectangle) -> bool {
+    corners.iter().flat_map(|&i| board.houses_of(i)).all(|&h| {
+        let inside = (0..4)
+            .filter(|&n| board.houses()[h].cells.contains(&corners[n]))
+            .collect::<Vec<_>>();
+        matches!(inside[..], [0, 1] | [2, 3] | [0, 2] | [1, 3])
+    })
+}
+
+/// Get the two corners sharing a side with the corner at `n`.
+fn neighbours(n: usize) -> [usize; 2] {
+    [n ^ 1, n ^ 2]
+}
+
+/// Split the corners of a rectangle into the floor, which only has the digits
+/// `ab` left, and the roof, which has more.
+fn split(board: &Board, corners: Rectangle, ab: Candidates) -> (Vec<usize>, Vec<usize>) {
+    (0..4).partition(|&n| board.candidates(corners[n]) == ab)
+}
+
+fn rectangle_step(
+    technique: Technique,
+    corners: Rectangle,
+    ab: Candidates,
+    houses: Vec<GroupKind>,
+    eliminations: Vec<(usize, u8)>,
+) -> Option<Step> {
+    if eliminations.is_empty() {
+        return None;
+    }
+
+    Some(Step {
+        technique,
+        houses,
+        cells: corners.to_vec(),
+        digits: ab.iter().collect(),
+        placements: Vec::new(),
+        eliminations,
+        pattern: None,
+    })
+}
+
+/// Call `f` with every rectangle and pair of digits that all of its corners
+/// still have.
+fn find(board: &Board, mut f: impl FnMut(Rectangle, Candidates) -> Option<Step>) -> Option<Step> {
+    if !board.unique() {
+        return None;
+    }
+
+    for corners in rectangles(board) {
+        let shared = corners.iter().fold(board.digits(), |acc, &i| {
+            acc.intersection(board.candidates(i))
+        });
+
+        for pair in combinations(&shared.iter().collect::<Vec<_>>(), 2) {
+            if let Some(step) = f(corners, Candidates::from_digits(pair)) {
+                return Some(step);
+            }
+        }
+    }
+
+    None
+}
+
+/// Find a rectangle where three corners only have the digits `ab` left.
+///
+/// If the last corner were `a` or `b` as well the puzzle would have two
+/// solutions, so both digits are removed from it.
+pub(super) fn unique_rectangle_1(board: &Board) -> Option<Step> {
+    find(board, |corners, ab| {
+        let (floor, roof) = split(board, corners, ab);
+        let [roof] = roof[..] else {
+            return None;
+        };
+        debug_assert_eq!(floor.len(), 3);
+
+        let eliminations = board.eliminations([corners[roof]], ab);
+        rectangle_step(
+            Technique::UniqueRectangle1,
+            corners,
+            ab,
+            Vec::new(),
+            eliminations,
+        )
+    })
+}
+
+/// Find the two roof corners of a rectangle, which share a side and whose
+/// floor only has the digits `ab` left.
+fn roof(board: &Board, corners: Rectangle, ab: Candidates) -> Option<[usize; 2]> {
+    let (floor, roof) = split(board, corners, ab);
+    match (&floor[..], &roof[..]) {
+        (&[a, b], &[c, d]) if neighbours(a).contains(&b) => Some([corners[c], corners[d]]),
+        _ => None,
+    }
+}
+
+/// Find a rectangle where the two roof corners have the digits `ab` and the
+/// same single extra digit.
+///
+/// One of the roof corners has to hold the extra digit to avoid a second
+/// solution, so it is removed from every cell that sees both of them.
+pub(super) fn unique_rectangle_2(board: &Board) -> Option<Step> {
+    find(board, |corners, ab| {
+        let [c, d] = roof(board, corners, ab)?;
+        let extra = board.candidates(c).difference(ab);
+        if extra.len() != 1 || board.candidates(d).difference(ab) != extra {
+            return None;
+        }
+
+        let targets = board
+            .empty_cells()
+            .filter(|&i| i != c && i != d && board.sees(i, c) && board.sees(i, d));
+        let eliminations = board.eliminations(targets, extra);
+        rectangle_step(
+            Technique::UniqueRectangle2,
+            corners,
+            ab,
+            Vec::new(),
+            eliminations,
+        )
+    })
+}
+
+/// Find a rectangle where the extra digits on the roof form a naked subset
+/// with other cells of a house both roof corners are in.
+///
+/// One of the roof corners has to hold an extra digit, so together they act
+/// as a single cell with only the extra digits, and the subset removes its
+/// digits from the rest of the house.
+pub(super) fn unique_rectangle_3(board: &Board) -> Option<Step> {
+    find(board, |corners, ab| {
+        let [c, d] = roof(board, corners, ab)?;
+        let extra = board
+            .candidates(c)
+            .union(board.candidates(d))
+            .difference(ab);
+
+        for &h in board.houses_of(c) {
+            let house = &board.houses()[h];
+            if !house.cells.contains(&d) {
+                continue;
+            }
+
+            let others = board
+                .empty_in(house)
+                .filter(|&i| {
+                    i != c && i != d && !board.candidates(i).intersection(extra).is_empty()
+                })
+                .collect::<Vec<_>>();
+
+            for n in 1..=3 {
+                for subset in combinations(&others, n) {
+                    let digits = subset
+                        .iter()
+                        .fold(extra, |acc, &i| acc.union(board.candidates(i)));
+                    if digits.len() as usize != n + 1 {
+                        continue;
+                    }
+
+                    let rest = board
+                        .empty_in(house)
+                        .filter(|&i| i != c && i != d && !subset.contains(&i));
+                    let eliminations = board.eliminations(rest, digits);
+                    if let Some(mut step) = rectangle_step(
+                        Technique::UniqueRectangle3,
+                        corners,
+                        ab,
+                        vec![house.kind],
+                        eliminations,
+                    ) {
+                        step.cells.extend(subset);
+                        return Some(step);
+                    }
+                }
+            }
+        }
+
+        None
+    })
+}
+
+/// Find a rectangle where one of the digits `ab` only has the two roof
+/// corners left in a house they are both in.
+///
+/// That digit has to go on one of the roof corners, and the other digit on
+/// that corner would leave a second solution, so it is removed from both.
+pub(super) fn unique_rectangle_4(board: &Board) -> Option<Step> {
+    find(board, |corners, ab| {
+        let [c, d] = roof(board, corners, ab)?;
+
+        for &h in board.houses_of(c) {
+            let house = &board.houses()[h];
+            if !house.cells.contains(&d) {
+                continue;
+            }
+
+            for a in ab.iter() {
+                if board.places(house, a) != [c.min(d), c.max(d)] {
+                    continue;
+                }
+
+                let b = ab.difference(Candidates::single(a));
+                let eliminations = board.eliminations([c, d], b);
+                if let Some(step) = rectangle_step(
+                    Technique::UniqueRectangle4,
+                    corners,
+                    ab,
+                    vec![house.kind],
+                    eliminations,
+                ) {
+                    return Some(step);
+                }
+            }
+        }
+
+        None
+    })
+}
+
+/// Find a grid where every empty cell has two digits left apart from a single
+/// cell with three, which is known as a bivalue universal grave.
+///
+/// With only two digits per cell, and every digit having two places in each
+/// house, the grid would have two solutions. One of the three digits has a
+/// third place in every house of the odd cell, and it must go there.
+pub(super) fn bug_plus_one(board: &Board) -> Option<Step> {
+    if !board.unique() {
+        return None;
+    }
+
+    let mut odd = None;
+    for i in board.empty_cells() {
+        match board.candidates(i).len() {
+            2 => (),
+            3 if odd.is_none() => odd = Some(i),
+            _ => return None,
+        }
+    }
+    let odd = odd?;
+
+    let digit = board.candidates(odd).iter().find(|&digit| {
+        board
+            .houses_of(odd)
+            .iter()
+            .all(|&h| board.places(&board.houses()[h], digit).len() == 3)
+    })?;
+
+    // Without that digit in the odd cell, every digit left has to have two
+    // places in every house for the grid to be a grave.
+    let grave = board.houses().iter().all(|house| {
+        board
+            .empty_in(house)
+            .flat_map(|i| board.candidates(i).iter())
+            .all(|d| {
+                let places = board.places(house, d);
+                let odd = usize::from(d == digit && places.contains(&odd));
+                places.len() - odd == 2
+            })
+    });
+    if !grave {
+        return None;
+    }
+
+    Some(Step {
+        technique: Technique::BivalueUniversalGrave,
+        houses: Vec::new(),
+        cells: vec![odd],
+        digits: vec![digit],
+        placements: vec![(odd, digit)],
+        eliminations: Vec::new(),
+        pattern: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variants::{HyperSudoku, StandardSudoku};
+
+    /// Create an empty board known to have a single solution, with the given
+    /// pencil marks.
+    fn board(cells: &[(usize, &[u8])]) -> Board {
+        let mut board = Board::new(&StandardSudoku::new());
+        board.unique = true;
+        for &(i, digits) in cells {
+            board.candidates[i] = Candidates::from_digits(digits.iter().copied());
+        }
+        board
+    }
+
+    #[test]
+    fn rectangles() {
+        let board = board(&[]);
+        let rectangles = super::rectangles(&board);
+        assert!(rectangles.contains(&[0, 3, 9, 12]));
+        assert!(!rectangles.contains(&[0, 1, 9, 10]));
+        assert!(!rectangles.contains(&[0, 3, 27, 30]));
+
+        // The first window only holds one of the corners.
+        let hyper = super::rectangles(&Board::new(&HyperSudoku::new()));
+        assert!(rectangles.contains(&[1, 4, 10, 13]));
+        assert!(!hyper.contains(&[1, 4, 10, 13]));
+        assert!(hyper.contains(&[0, 4, 9, 13]));
+    }
+
+    #[test]
+    fn unique_rectangle_1() {
+        let mut board = board(&[(0, &[1, 2]), (3, &[1, 2]), (9, &[1, 2]), (12, &[1, 2, 5])]);
+
+        let step = super::unique_rectangle_1(&board).unwrap();
+        assert_eq!(step.technique, Technique::UniqueRectangle1);
+        assert_eq!(step.cells, [0, 3, 9, 12]);
+        assert_eq!(step.eliminations, [(12, 1), (12, 2)]);
+
+        board.unique = false;
+        assert!(super::unique_rectangle_1(&board).is_none());
+    }
+
+    #[test]
+    fn unique_rectangle_2() {
+        let board = board(&[
+            (0, &[1, 2]),
+            (3, &[1, 2]),
+            (9, &[1, 2, 5]),
+            (12, &[1, 2, 5]),
+        ]);
+
+        let step = super::unique_rectangle_2(&board).unwrap();
+        assert_eq!(step.technique, Technique::UniqueRectangle2);
+        assert_eq!(step.eliminations.len(), 7);
+        assert!(step.eliminations.iter().all(|&(i, d)| d == 5 && i / 9 == 1));
+    }
+
+    #[test]
+    fn unique_rectangle_3() {
+        // The roof has 3 and 4 on top of 1 and 2, and r1c8 only has 3 and 4,
+        // so they are removed from the rest of the row.
+        let board = board(&[
+            (0, &[1, 2]),
+            (3, &[1, 2]),
+            (9, &[1, 2, 3]),
+            (12, &[1, 2, 4]),
+            (17, &[3, 4]),
+        ]);
+
+        let step = super::unique_rectangle_3(&board).unwrap();
+        assert_eq!(step.technique, Technique::UniqueRectangle3);
+        assert_eq!(step.houses, [GroupKind::Row(1)]);
+        assert_eq!(step.cells, [0, 3, 9, 12, 17]);
+        assert_eq!(step.eliminations.len(), 12);
+    }
+
+    #[test]
+    fn unique_rectangle_4() {
+        // 1 can only go on the roof in the second row, so 2 is removed from it.
+        let mut board = board(&[
+            (0, &[1, 2]),
+            (3, &[1, 2]),
+            (9, &[1, 2, 3]),
+            (12, &[1, 2, 4]),
+        ]);
+        for i in [10, 11, 13, 14, 15, 16, 17] {
+            board.candidates[i].remove(1);
+        }
+
+        let step = super::unique_rectangle_4(&board).unwrap();
+        assert_eq!(step.technique, Technique::UniqueRectangle4);
+        assert_eq!(step.houses, [GroupKind::Row(1)]);
+        assert_eq!(step.eliminations, [(9, 2), (12, 2)]);
+    }
+
+    /// Create a grave from a solution, where every cell can also have the
+    /// next digit, with a third digit in cell 0.
+    fn grave() -> Board {
+        let solution =
+            "827154396965327148341689752593468271472513689618972435786235914154796823239841567";
+        let mut board = board(&[]);
+        for (i, digit) in solution.bytes().enumerate() {
+            let digit = digit - b'0';
+            board.candidates[i] = Candidates::from_digits([digit, digit % 9 + 1]);
+        }
+        board.candidates[0] = Candidates::from_digits([8, 9, 5]);
+        board
+    }
+
+    #[test]
+    fn bug_plus_one() {
+        let board = grave();
+        let step = super::bug_plus_one(&board).unwrap();
+        assert_eq!(step.technique, Technique::BivalueUniversalGrave);
+        assert_eq!(step.placements, [(0, 5)]);
+    }
+
+    #[test]
+    fn not_a_grave() {
+        // Cell 40 swaps 2 for 3, leaving three places for 3 in its houses and
+        // one for 2, so the rest of the grid isn't a grave.
+        let mut board = grave();
+        assert_eq!(board.candidates[40], Candidates::from_digits([1, 2]));
+        board.candidates[40] = Candidates::from_digits([1, 3]);
+        assert!(super::bug_plus_one(&board).is_none());
+    }
+}
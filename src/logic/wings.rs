@@ -0,0 +1,245 @@
+use super::chains::alternating;
+use super::{Board, Pattern, Step, Technique};
+use crate::solver::Candidates;
+
+fn bivalue_cells(board: &Board) -> Vec<usize> {
+    board
+        .empty_cells()
+        .filter(|&i| board.candidates(i).len() == 2)
+        .collect()
+}
+
+/// Get the empty cells, other than those in `pattern`, that see every cell
+/// in `pattern`.
+fn seen_by_all<'a>(board: &'a Board, pattern: &'a [usize]) -> impl Iterator<Item = usize> + 'a {
+    board
+        .empty_cells()
+        .filter(|i| !pattern.contains(i))
+        .filter(|&i| pattern.iter().all(|&p| board.sees(i, p)))
+}
+
+/// Find a pivot cell with the digits `xy` that sees a pincer with `xz` and a
+/// pincer with `yz`.
+///
+/// Whichever digit goes on the pivot, one of the pincers has to be `z`, so
+/// `z` can be removed from every cell that sees both pincers.
+pub(super) fn xy_wing(board: &Board) -> Option<Step> {
+    let bivalue = bivalue_cells(board);
+
+    for &pivot in &bivalue {
+        let xy = board.candidates(pivot);
+        let (Some(x), Some(y)) = (xy.first(), xy.iter().nth(1)) else {
+            continue;
+        };
+
+        let pincers = bivalue
+            .iter()
+            .copied()
+            .filter(|&i| i != pivot && board.sees(i, pivot))
+            .collect::<Vec<_>>();
+
+        for &a in &pincers {
+            let xz = board.candidates(a);
+            if !xz.contains(x) || xz.contains(y) {
+                continue;
+            }
+            let Some(z) = xz.difference(Candidates::single(x)).first() else {
+                continue;
+            };
+
+            for &b in &pincers {
+                if board.candidates(b) != Candidates::from_digits([y, z]) {
+                    continue;
+                }
+
+                let targets = seen_by_all(board, &[a, b]).collect::<Vec<_>>();
+                let eliminations = board.eliminations(targets, Candidates::single(z));
+                if !eliminations.is_empty() {
+                    return Some(Step {
+                        technique: Technique::XYWing,
+                        houses: Vec::new(),
+                        cells: vec![pivot, a, b],
+                        digits: vec![x, y, z],
+                        placements: Vec::new(),
+                        eliminations,
+                        pattern: Some(Pattern::Chain(alternating(&[
+                            (a, z),
+                            (a, x),
+                            (pivot, x),
+                            (pivot, y),
+                            (b, y),
+                            (b, z),
+                        ]))),
+                    });
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Find a pivot cell with the digits `xyz` that sees a pincer with `xz` and
+/// a pincer with `yz`.
+///
+/// One of the three cells has to be `z`, so `z` can be removed from every
+/// cell that sees all three.
+pub(super) fn xyz_wing(board: &Board) -> Option<Step> {
+    let bivalue = bivalue_cells(board);
+
+    for pivot in board.empty_cells() {
+        let xyz = board.candidates(pivot);
+        if xyz.len() != 3 {
+            continue;
+        }
+
+        let pincers = bivalue
+            .iter()
+            .copied()
+            .filter(|&i| board.sees(i, pivot))
+            .filter(|&i| board.candidates(i).difference(xyz).is_empty())
+            .collect::<Vec<_>>();
+
+        for (n, &a) in pincers.iter().enumerate() {
+            for &b in &pincers[n + 1..] {
+                let shared = board.candidates(a).intersection(board.candidates(b));
+                if shared.len() != 1 {
+                    continue;
+                }
+                let targets = seen_by_all(board, &[pivot, a, b]).collect::<Vec<_>>();
+                let eliminations = board.eliminations(targets, shared);
+                if !eliminations.is_empty() {
+                    return Some(Step {
+                        technique: Technique::XYZWing,
+                        houses: Vec::new(),
+                        cells: vec![pivot, a, b],
+                        digits: xyz.iter().collect(),
+                        placements: Vec::new(),
+                        eliminations,
+                        pattern: None,
+                    });
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Find two cells with the same digits `xy` that don't see each other, where
+/// `y` only has two places left in some group and each of those places sees
+/// one of the cells.
+///
+/// One of the two places has to be `y`, so one of the cells has to be `x`,
+/// and `x` can be removed from every cell that sees both of them.
+pub(super) fn w_wing(board: &Board) -> Option<Step> {
+    let bivalue = bivalue_cells(board);
+
+    for (n, &a) in bivalue.iter().enumerate() {
+        for &b in &bivalue[n + 1..] {
+            let xy = board.candidates(a);
+            if board.candidates(b) != xy || board.sees(a, b) {
+                continue;
+            }
+
+            for y in xy.iter() {
+                let Some(x) = xy.difference(Candidates::single(y)).first() else {
+                    continue;
+                };
+
+                for house in board.houses() {
+                    let [c, d] = board.places(house, y)[..] else {
+                        continue;
+                    };
+                    if [c, d].iter().any(|i| *i == a || *i == b) {
+                        continue;
+                    }
+
+                    let (c, d) = if board.sees(c, a) && board.sees(d, b) {
+                        (c, d)
+                    } else if board.sees(d, a) && board.sees(c, b) {
+                        (d, c)
+                    } else {
+                        continue;
+                    };
+
+                    let targets = seen_by_all(board, &[a, b]).collect::<Vec<_>>();
+                    let eliminations = board.eliminations(targets, Candidates::single(x));
+                    if !eliminations.is_empty() {
+                        return Some(Step {
+                            technique: Technique::WWing,
+                            houses: vec![house.kind],
+                            cells: vec![a, b, c, d],
+                            digits: vec![x, y],
+                            placements: Vec::new(),
+                            eliminations,
+                            pattern: Some(Pattern::Chain(alternating(&[
+                                (a, x),
+                                (a, y),
+                                (c, y),
+                                (d, y),
+                                (b, y),
+                                (b, x),
+                            ]))),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variants::StandardSudoku;
+
+    /// Create an empty board with the given pencil marks.
+    fn board(cells: &[(usize, &[u8])]) -> Board {
+        let mut board = Board::new(&StandardSudoku::new());
+        for &(i, digits) in cells {
+            board.candidates[i] = Candidates::from_digits(digits.iter().copied());
+        }
+        board
+    }
+
+    #[test]
+    fn xy_wing() {
+        // Pivot on r0c0, pincers on r0c5 and r4c0.
+        let board = board(&[(0, &[1, 2]), (5, &[1, 3]), (36, &[2, 3])]);
+
+        let step = super::xy_wing(&board).unwrap();
+        assert_eq!(step.technique, Technique::XYWing);
+        assert_eq!(step.cells, [0, 5, 36]);
+        assert_eq!(step.eliminations, [(41, 3)]);
+        assert!(matches!(&step.pattern, Some(Pattern::Chain(chain)) if chain.len() == 6));
+    }
+
+    #[test]
+    fn xyz_wing() {
+        // Pivot on r0c0, pincers on r0c5 and r1c1.
+        let board = board(&[(0, &[1, 2, 3]), (5, &[1, 3]), (10, &[2, 3])]);
+
+        let step = super::xyz_wing(&board).unwrap();
+        assert_eq!(step.technique, Technique::XYZWing);
+        assert_eq!(step.eliminations, [(1, 3), (2, 3)]);
+        assert_eq!(step.pattern, None);
+    }
+
+    #[test]
+    fn w_wing() {
+        // r0c0 and r4c4 both have 1 and 2, and 2 can only go on r0c8 and
+        // r4c8 in the last column.
+        let mut board = board(&[(0, &[1, 2]), (40, &[1, 2])]);
+        for row in [1, 2, 3, 5, 6, 7, 8] {
+            board.candidates[row * 9 + 8].remove(2);
+        }
+
+        let step = super::w_wing(&board).unwrap();
+        assert_eq!(step.technique, Technique::WWing);
+        assert_eq!(step.cells, [0, 40, 8, 44]);
+        assert_eq!(step.eliminations, [(4, 1), (36, 1)]);
+    }
+}
@@ -0,0 +1,687 @@
+//! Constraint propagation search for solving Sudoku grids.
+//!
+//! Every cell keeps a bitmask of the digits that can still be placed in it.
+//! Placing a digit removes it from the candidates of every peer of the cell,
+//! and any cell or group that is left with a single option is filled in right
+//! away, and a digit that is confined to the overlap of two groups is removed
+//! from the rest of both. The cells of a cage only keep the digits that are
+//! part of some combination adding up to its sum, and every constraint of the
+//! grid removes the digits it rules out. When propagation stalls,
+//! the search guesses on the cell with the fewest candidates left, and
+//! backtracks if the guess leads to a contradiction.
+
+use crate::constraint::Constraint;
+use crate::sudoku::{Cell, Sudoku};
+use std::ops::{Range, RangeInclusive};
+use std::sync::Arc;
+
+/// A set of digits from 0 to 31, stored as a bitmask where bit `n` is set if
+/// digit `n` is part of the set.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Candidates(u32);
+
+/// Get the bit of `digit` in the mask of a [`Candidates`].
+fn bit(digit: u8) -> u32 {
+    assert!(digit < 32, "digit {digit} does not fit in a set of candidates");
+    1 << digit
+}
+
+impl Candidates {
+    /// Create a set with all the digits in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range goes past 31.
+    pub fn from_range(range: RangeInclusive<u8>) -> Self {
+        Self(range.fold(0, |mask, d| mask | bit(d)))
+    }
+
+    /// Create a set from the digits in `digits`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the digits is above 31.
+    pub fn from_digits(digits: impl IntoIterator<Item = u8>) -> Self {
+        digits
+            .into_iter()
+            .fold(Self::default(), |set, d| set.union(Self::single(d)))
+    }
+
+    /// Create a set containing only `digit`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `digit` is above 31.
+    pub fn single(digit: u8) -> Self {
+        Self(bit(digit))
+    }
+
+    /// Check if `digit` is in the set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `digit` is above 31.
+    pub fn contains(self, digit: u8) -> bool {
+        self.0 & bit(digit) != 0
+    }
+
+    /// Remove `digit` from the set, returning whether it was present.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `digit` is above 31.
+    pub fn remove(&mut self, digit: u8) -> bool {
+        let present = self.contains(digit);
+        self.0 &= !bit(digit);
+        present
+    }
+
+    /// Get the digits that are in either set.
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// Get the digits that are in both sets.
+    pub fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    /// Get the digits that are in this set but not in `other`.
+    pub fn difference(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    /// Get the number of digits in the set.
+    pub fn len(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// Check if the set has no digits.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Get the lowest digit in the set.
+    pub fn first(self) -> Option<u8> {
+        (!self.is_empty()).then(|| self.0.trailing_zeros() as u8)
+    }
+
+    /// Iterate over the digits in the set, from lowest to highest.
+    pub fn iter(self) -> impl Iterator<Item = u8> {
+        (0..32).filter(move |&d| self.contains(d))
+    }
+}
+
+/// Two groups that share more than one cell, as ranges of
+/// [`Layout::overlaps`].
+struct Intersection {
+    shared: Range<usize>,
+    /// The cells of each group that are not in the other one.
+    rest: [Range<usize>; 2],
+}
+
+/// A cage along with every set of digits that adds up to its sum.
+struct Sums {
+    cells: Vec<usize>,
+    combinations: Vec<Candidates>,
+}
+
+/// Get every set of `len` different digits from `digits` that adds up to
+/// `sum`.
+fn combinations(digits: Candidates, len: usize, sum: u32) -> Vec<Candidates> {
+    if len == 0 {
+        return match sum {
+            0 => vec![Candidates::default()],
+            _ => Vec::new(),
+        };
+    }
+
+    // Only digits above the one picked are left for the rest of the set, so
+    // that every set is found once.
+    let mut found = Vec::new();
+    let mut rest = digits;
+    for digit in digits.iter() {
+        rest.remove(digit);
+        if let Some(left) = sum.checked_sub(digit.into()) {
+            found.extend(
+                combinations(rest, len - 1, left)
+                    .into_iter()
+                    .map(|set| set.union(Candidates::single(digit))),
+            );
+        }
+    }
+    found
+}
+
+/// The shape of a grid, precomputed once before the search starts.
+pub(crate) struct Layout {
+    groups: Vec<Vec<usize>>,
+    peers: Vec<Vec<usize>>,
+    intersections: Vec<Intersection>,
+    overlaps: Vec<usize>,
+    cages: Vec<Sums>,
+    constraints: Vec<Arc<dyn Constraint>>,
+    digits: Candidates,
+}
+
+impl Layout {
+    /// Compute the layout of `sudoku`.
+    pub fn new(sudoku: &impl Sudoku) -> Self {
+        let groups = sudoku.group_indices();
+        let cages = sudoku.cages();
+        let len = sudoku.cells().len();
+        let digits = Candidates::from_range(sudoku.cell_values());
+
+        let mut memberships = vec![Vec::new(); len];
+        for (g, group) in groups.iter().enumerate() {
+            for &i in group {
+                memberships[i].push(g);
+            }
+        }
+
+        let mut peers = vec![Vec::new(); len];
+        let mut seen = vec![usize::MAX; len];
+        for (i, p) in peers.iter_mut().enumerate() {
+            seen[i] = i;
+            for &g in &memberships[i] {
+                for &j in &groups[g] {
+                    if seen[j] != i {
+                        seen[j] = i;
+                        p.push(j);
+                    }
+                }
+            }
+            for cage in cages.iter().filter(|cage| cage.cells.contains(&i)) {
+                for &j in &cage.cells {
+                    if seen[j] != i {
+                        seen[j] = i;
+                        p.push(j);
+                    }
+                }
+            }
+            p.sort_unstable();
+        }
+
+        // Collect the cells that each group has in common with every later
+        // group, going through the groups of its cells rather than comparing
+        // it with every other group.
+        let mut intersections = Vec::new();
+        let mut overlaps = Vec::new();
+        let mut shared = vec![Vec::new(); groups.len()];
+        for (a, group) in groups.iter().enumerate() {
+            for &i in group {
+                for &b in memberships[i].iter().filter(|&&b| b > a) {
+                    shared[b].push(i);
+                }
+            }
+
+            for (b, shared) in shared.iter_mut().enumerate().skip(a + 1) {
+                if shared.len() > 1 {
+                    let mut extend = |cells: &mut dyn Iterator<Item = usize>| {
+                        let start = overlaps.len();
+                        overlaps.extend(cells);
+                        start..overlaps.len()
+                    };
+                    let rest = [a, b].map(|g| {
+                        extend(&mut groups[g].iter().copied().filter(|i| !shared.contains(i)))
+                    });
+                    intersections.push(Intersection {
+                        shared: extend(&mut shared.iter().copied()),
+                        rest,
+                    });
+                }
+                shared.clear();
+            }
+        }
+
+        let cages = cages
+            .into_iter()
+            .map(|cage| Sums {
+                combinations: combinations(digits, cage.cells.len(), cage.sum),
+                cells: cage.cells,
+            })
+            .collect();
+
+        Self {
+            groups,
+            peers,
+            intersections,
+            overlaps,
+            cages,
+            constraints: sudoku.constraints(),
+            digits,
+        }
+    }
+}
+
+/// A partially solved grid along with the candidates of every cell.
+#[derive(Clone)]
+pub(crate) struct State {
+    cells: Vec<Cell>,
+    candidates: Vec<Candidates>,
+}
+
+impl State {
+    /// Create a state from the given cells, propagating the consequences of
+    /// every set cell.
+    ///
+    /// Returns [`None`] if the cells lead to a contradiction.
+    pub fn new(layout: &Layout, cells: &[Cell]) -> Option<Self> {
+        let mut state = Self {
+            cells: vec![None; cells.len()],
+            candidates: vec![layout.digits; cells.len()],
+        };
+
+        let givens = cells
+            .iter()
+            .enumerate()
+            .filter_map(|(i, c)| c.map(|d| (i, d)))
+            .collect();
+
+        state.propagate(layout, givens).then_some(state)
+    }
+
+    /// Place `digit` on cell `i` and propagate the consequences.
+    ///
+    /// Returns `false` if the placement leads to a contradiction.
+    fn place(&mut self, layout: &Layout, i: usize, digit: u8) -> bool {
+        self.propagate(layout, vec![(i, digit)])
+    }
+
+    fn propagate(&mut self, layout: &Layout, mut queue: Vec<(usize, u8)>) -> bool {
+        loop {
+            while let Some((i, digit)) = queue.pop() {
+                match self.cells[i] {
+                    Some(d) if d == digit => continue,
+                    Some(_) => return false,
+                    None if !self.candidates[i].contains(digit) => return false,
+                    None => (),
+                }
+
+                self.cells[i] = Some(digit);
+                self.candidates[i] = Candidates::single(digit);
+
+                for &p in &layout.peers[i] {
+                    let candidates = &mut self.candidates[p];
+                    if candidates.remove(digit) {
+                        if candidates.is_empty() {
+                            return false;
+                        }
+                        if self.cells[p].is_none() && candidates.len() == 1 {
+                            queue.push((p, candidates.first().unwrap()));
+                        }
+                    }
+                }
+            }
+
+            if !self.hidden_singles(layout, &mut queue) {
+                return false;
+            }
+
+            if queue.is_empty() {
+                // The cheaper passes go first, and the later ones only run
+                // once the earlier ones have nothing left to remove.
+                let mut removed = Some(false);
+                for pass in [Self::sums, Self::constraints, Self::intersections] {
+                    removed = pass(self, layout, &mut queue);
+                    if removed != Some(false) {
+                        break;
+                    }
+                }
+
+                match removed {
+                    None => return false,
+                    Some(false) => return true,
+                    Some(true) => (),
+                }
+            }
+        }
+    }
+
+    /// Remove the digits that aren't part of any combination that adds up to
+    /// the sum of their cage, queueing any cell left with a single option.
+    ///
+    /// Returns whether any candidate was removed, or [`None`] if some cage has
+    /// no combination left.
+    fn sums(&mut self, layout: &Layout, queue: &mut Vec<(usize, u8)>) -> Option<bool> {
+        let mut removed = false;
+
+        for cage in &layout.cages {
+            let mut placed = Candidates::default();
+            let mut options = Candidates::default();
+            for &i in &cage.cells {
+                match self.cells[i] {
+                    Some(digit) => placed = placed.union(Candidates::single(digit)),
+                    None => options = options.union(self.candidates[i]),
+                }
+            }
+
+            // Every combination has to have the placed digits, and the rest
+            // of its digits have to fit in the empty cells.
+            let allowed = cage
+                .combinations
+                .iter()
+                .filter(|set| placed.difference(**set).is_empty())
+                .map(|set| set.difference(placed))
+                .filter(|rest| rest.difference(options).is_empty())
+                .filter(|rest| {
+                    cage.cells.iter().all(|&i| {
+                        self.cells[i].is_some()
+                            || !self.candidates[i].intersection(*rest).is_empty()
+                    })
+                })
+                .reduce(Candidates::union)?;
+
+            for &i in &cage.cells {
+                if self.cells[i].is_some() {
+                    continue;
+                }
+
+                let candidates = &mut self.candidates[i];
+                let left = candidates.intersection(allowed);
+                if left == *candidates {
+                    continue;
+                }
+                if left.is_empty() {
+                    return None;
+                }
+
+                *candidates = left;
+                removed = true;
+                if left.len() == 1 {
+                    queue.push((i, left.first().unwrap()));
+                }
+            }
+        }
+
+        Some(removed)
+    }
+
+    /// Let every constraint remove the digits it rules out, queueing any cell
+    /// left with a single option.
+    ///
+    /// Returns whether any candidate was removed, or [`None`] if some cell has
+    /// no candidates left.
+    fn constraints(&mut self, layout: &Layout, queue: &mut Vec<(usize, u8)>) -> Option<bool> {
+        if layout.constraints.is_empty() {
+            return Some(false);
+        }
+
+        let before = self.candidates.clone();
+        for constraint in &layout.constraints {
+            constraint.propagate(&self.cells, &mut self.candidates);
+        }
+
+        let mut removed = false;
+        for (i, before) in before.into_iter().enumerate() {
+            // Constraints can only take candidates away.
+            let left = before.intersection(self.candidates[i]);
+            self.candidates[i] = left;
+            if left == before {
+                continue;
+            }
+            if left.is_empty() {
+                return None;
+            }
+
+            removed = true;
+            if self.cells[i].is_none() && left.len() == 1 {
+                queue.push((i, left.first().unwrap()));
+            }
+        }
+
+        Some(removed)
+    }
+
+    /// Remove the digits that are confined to the overlap of two groups from
+    /// the rest of both groups, queueing any cell left with a single option.
+    ///
+    /// Returns whether any candidate was removed, or [`None`] if some cell has
+    /// no candidates left.
+    fn intersections(&mut self, layout: &Layout, queue: &mut Vec<(usize, u8)>) -> Option<bool> {
+        let mut removed = false;
+
+        for intersection in &layout.intersections {
+            let shared = &layout.overlaps[intersection.shared.clone()];
+            if shared.iter().all(|&i| self.cells[i].is_some()) {
+                continue;
+            }
+
+            let union = |cells: &[usize]| {
+                cells.iter().fold(Candidates::default(), |set, &i| {
+                    set.union(self.candidates[i])
+                })
+            };
+            let rest = intersection
+                .rest
+                .clone()
+                .map(|range| &layout.overlaps[range]);
+            let shared = union(shared);
+            let outside = rest.map(union);
+
+            for (side, rest) in rest.iter().enumerate() {
+                let locked = shared.difference(outside[1 - side]);
+                if locked.intersection(outside[side]).is_empty() {
+                    continue;
+                }
+
+                for &i in *rest {
+                    let candidates = &mut self.candidates[i];
+                    let left = candidates.difference(locked);
+                    if left == *candidates {
+                        continue;
+                    }
+                    if left.is_empty() {
+                        return None;
+                    }
+
+                    *candidates = left;
+                    removed = true;
+                    if self.cells[i].is_none() && left.len() == 1 {
+                        queue.push((i, left.first().unwrap()));
+                    }
+                }
+            }
+        }
+
+        Some(removed)
+    }
+
+    /// Queue every digit that only has a single place left in a group.
+    ///
+    /// Returns `false` if some digit has no place left in a group.
+    fn hidden_singles(&self, layout: &Layout, queue: &mut Vec<(usize, u8)>) -> bool {
+        for group in &layout.groups {
+            let mut once = 0;
+            let mut twice = 0;
+            for &i in group {
+                twice |= once & self.candidates[i].0;
+                once |= self.candidates[i].0;
+            }
+
+            if once != layout.digits.0 {
+                return false;
+            }
+
+            for digit in Candidates(once & !twice).iter() {
+                let i = group
+                    .iter()
+                    .copied()
+                    .find(|&i| self.candidates[i].contains(digit))
+                    .unwrap();
+                if self.cells[i].is_none() {
+                    queue.push((i, digit));
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Find the empty cell with the fewest candidates left.
+    fn most_constrained(&self) -> Option<usize> {
+        (0..self.cells.len())
+            .filter(|&i| self.cells[i].is_none())
+            .min_by_key(|&i| self.candidates[i].len())
+    }
+}
+
+/// A depth-first search through every solution of a grid.
+///
+/// The search is lazy, and each call to [`Iterator::next`] resumes from where
+/// the last solution was found.
+pub(crate) struct Search {
+    layout: Layout,
+    stack: Vec<(State, usize, Candidates)>,
+    pending: Option<State>,
+    guesses: usize,
+    limit: Option<usize>,
+}
+
+impl Search {
+    /// Start a search through the solutions of `sudoku`.
+    pub fn new(sudoku: &impl Sudoku) -> Self {
+        let layout = Layout::new(sudoku);
+        let pending = State::new(&layout, sudoku.cells());
+
+        Self {
+            layout,
+            stack: Vec::new(),
+            pending,
+            guesses: 0,
+            limit: None,
+        }
+    }
+
+    /// Give up the search after `limit` guesses.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Get the number of guesses the search may make.
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /// Check if the search was stopped by its guess limit.
+    pub fn exceeded(&self) -> bool {
+        self.limit.is_some_and(|limit| self.guesses > limit)
+    }
+}
+
+impl Iterator for Search {
+    type Item = Vec<Cell>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(state) = self.pending.take() {
+                match state.most_constrained() {
+                    None => return Some(state.cells),
+                    Some(i) => {
+                        let candidates = state.candidates[i];
+                        self.stack.push((state, i, candidates));
+                    }
+                }
+            }
+
+            let (state, i, remaining) = self.stack.last_mut()?;
+            let i = *i;
+            let Some(digit) = remaining.first() else {
+                self.stack.pop();
+                continue;
+            };
+            remaining.remove(digit);
+
+            self.guesses += 1;
+            if self.limit.is_some_and(|limit| self.guesses > limit) {
+                self.stack.clear();
+                return None;
+            }
+
+            // The last guess on a cell can reuse the state instead of copying
+            // it, since there is nothing left to backtrack to.
+            let mut next = if remaining.is_empty() {
+                self.stack.pop().unwrap().0
+            } else {
+                state.clone()
+            };
+
+            if next.place(&self.layout, i, digit) {
+                self.pending = Some(next);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn candidates() {
+        let mut candidates = Candidates::from_range(1..=9);
+        assert_eq!(candidates.len(), 9);
+        assert!(candidates.contains(1));
+        assert!(candidates.contains(9));
+        assert!(!candidates.contains(0));
+
+        assert!(candidates.remove(1));
+        assert!(!candidates.remove(1));
+        assert_eq!(candidates.first(), Some(2));
+        assert_eq!(
+            candidates.iter().collect::<Vec<_>>(),
+            (2..=9).collect::<Vec<_>>()
+        );
+
+        assert_eq!(Candidates::single(4).first(), Some(4));
+        assert!(Candidates::default().is_empty());
+        assert_eq!(Candidates::from_range(1..=31).len(), 31);
+    }
+
+    #[test]
+    #[should_panic(expected = "digit 32 does not fit in a set of candidates")]
+    fn candidates_too_large() {
+        Candidates::from_range(1..=32);
+    }
+
+    #[test]
+    fn combinations() {
+        let digits = Candidates::from_range(1..=9);
+        assert_eq!(
+            super::combinations(digits, 2, 4),
+            [Candidates::from_digits([1, 3])]
+        );
+        assert_eq!(super::combinations(digits, 3, 15).len(), 8);
+        assert_eq!(
+            super::combinations(digits, 9, 45),
+            [Candidates::from_range(1..=9)]
+        );
+        assert!(super::combinations(digits, 2, 18).is_empty());
+    }
+
+    #[test]
+    fn intersections() {
+        let sudoku = crate::StandardSudoku::from_str(
+            "   75       2 8 45 5      2 7   1   8 1 492 7  9 2 6  41      8  3   4    8  456 ",
+        )
+        .unwrap();
+        let layout = Layout::new(&sudoku);
+
+        // Every subgrid overlaps three rows and three columns.
+        assert_eq!(layout.intersections.len(), 9 * 6);
+        let first = &layout.intersections[0];
+        assert_eq!(layout.overlaps[first.shared.clone()], [0, 1, 2]);
+        assert_eq!(layout.overlaps[first.rest[0].clone()], [3, 4, 5, 6, 7, 8]);
+        assert_eq!(
+            layout.overlaps[first.rest[1].clone()],
+            [9, 10, 11, 18, 19, 20]
+        );
+
+        // The puzzle needs a pointing pair, which propagation finds without
+        // having to guess.
+        let state = State::new(&layout, sudoku.cells()).unwrap();
+        assert_eq!(state.most_constrained(), None);
+    }
+}
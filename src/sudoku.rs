@@ -6,9 +6,12 @@
 //! when all remaining cells in the grid have been filled out according to the
 //! games rules.
 
-use rustc_hash::FxHashSet;
+use crate::constraint::Constraint;
+use crate::error::SudokuError;
+use crate::solver::Search;
 use std::fmt::Display;
 use std::ops::RangeInclusive;
+use std::sync::Arc;
 
 /// The storage value for a cell on the Suduko grid.
 ///
@@ -16,8 +19,108 @@ use std::ops::RangeInclusive;
 /// is [`None`].
 pub type Cell = Option<u8>;
 
+/// The kind of a group of cells, along with its index among the groups of the
+/// same kind.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GroupKind {
+    /// A row, counted from the top.
+    Row(usize),
+    /// A column, counted from the left.
+    Column(usize),
+    /// A subgrid, counted left to right, top to bottom.
+    Grid(usize),
+    /// One of the extra subgrids of hyper Sudoku.
+    Window(usize),
+    /// One of the irregular regions of jigsaw Sudoku.
+    Region(usize),
+    /// One of the main diagonals of diagonal Sudoku, starting from the top
+    /// left corner for the first one and the top right corner for the second.
+    Diagonal(usize),
+    /// One of the cages of killer Sudoku.
+    Cage(usize),
+    /// One of the constraints added on top of the grid, as ordered by
+    /// [`Sudoku::constraints`].
+    Constraint(usize),
+}
+
+impl Display for GroupKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Row(i) => write!(f, "row {i}"),
+            Self::Column(i) => write!(f, "column {i}"),
+            Self::Grid(i) => write!(f, "subgrid {i}"),
+            Self::Window(i) => write!(f, "window {i}"),
+            Self::Region(i) => write!(f, "region {i}"),
+            Self::Diagonal(i) => write!(f, "diagonal {i}"),
+            Self::Cage(i) => write!(f, "cage {i}"),
+            Self::Constraint(i) => write!(f, "constraint {i}"),
+        }
+    }
+}
+
+/// Two cells with the same digit in a group they share, or that break one of
+/// the constraints of the grid together.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Conflict {
+    /// The indices of the clashing cells, lowest first.
+    pub cells: (usize, usize),
+    /// The index of the group they clash in, as ordered by
+    /// [`Sudoku::groups`], with the cages of [`Sudoku::cages`] and then the
+    /// constraints of [`Sudoku::constraints`] counted after the groups.
+    pub group: usize,
+    /// The kind of group they clash in.
+    pub kind: GroupKind,
+}
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "cells {} and {} clash in {}",
+            self.cells.0, self.cells.1, self.kind
+        )
+    }
+}
+
+/// A set of cells whose digits must add up to a sum, without repeating a
+/// digit.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Cage {
+    /// The indices of the cells in the cage.
+    pub cells: Vec<usize>,
+    /// The sum of the digits in the cage.
+    pub sum: u32,
+}
+
+impl Cage {
+    /// Check if the digits set in the cage can still add up to its sum, once
+    /// its empty cells are filled with other digits from `values`.
+    ///
+    /// Repeated digits are not checked here, as they are reported by
+    /// [`Sudoku::conflicts`].
+    pub fn reachable(&self, cells: &[Cell], values: RangeInclusive<u8>) -> bool {
+        let digits = self.cells.iter().filter_map(|&i| cells[i]);
+        let placed = digits.clone().map(u32::from).sum::<u32>();
+        let empty = self.cells.len() - digits.clone().count();
+
+        let mut unused = values
+            .filter(|d| !digits.clone().any(|placed| placed == *d))
+            .map(u32::from)
+            .collect::<Vec<_>>();
+        if unused.len() < empty {
+            return false;
+        }
+
+        let lowest = unused[..empty].iter().sum::<u32>();
+        unused.reverse();
+        let highest = unused[..empty].iter().sum::<u32>();
+
+        (placed + lowest..=placed + highest).contains(&self.sum)
+    }
+}
+
 /// A game of suduko.
-pub trait Sudoku: Sized + Display {
+pub trait Sudoku: Sized + Clone + Display {
     /// Get the number on a cell on the grid.
     ///
     /// # Panics
@@ -37,7 +140,7 @@ pub trait Sudoku: Sized + Display {
     fn cells_mut(&mut self) -> &mut [Cell];
 
     /// Get all possible valid values for the cells.
-    fn cell_values(&mut self) -> RangeInclusive<u8>;
+    fn cell_values(&self) -> RangeInclusive<u8>;
 
     /// Get all the rows.
     fn rows(&self) -> Vec<Vec<Cell>>;
@@ -46,6 +149,26 @@ pub trait Sudoku: Sized + Display {
     /// Get all the subgrids.
     fn grids(&self) -> Vec<Vec<Cell>>;
 
+    /// Get the indices of the cells in every group.
+    ///
+    /// The groups are in the same order as in [`Self::groups`].
+    fn group_indices(&self) -> Vec<Vec<usize>>;
+
+    /// Get the kind of a group, where `group` is the index of the group as
+    /// ordered by [`Self::groups`].
+    fn group_kind(&self, group: usize) -> GroupKind {
+        let rows = self.rows().len();
+        let columns = self.columns().len();
+
+        if group < rows {
+            GroupKind::Row(group)
+        } else if group < rows + columns {
+            GroupKind::Column(group - rows)
+        } else {
+            GroupKind::Grid(group - rows - columns)
+        }
+    }
+
     /// Get all groups a cell is part of.
     fn groups_of(&self, i: usize) -> Vec<Vec<Cell>>;
 
@@ -60,6 +183,22 @@ pub trait Sudoku: Sized + Display {
         v
     }
 
+    /// Get the cages of the grid, whose digits must add up to a sum on top of
+    /// the rules of the groups.
+    ///
+    /// Most variants have no cages.
+    fn cages(&self) -> Vec<Cage> {
+        Vec::new()
+    }
+
+    /// Get the rules of the grid that go beyond its groups and cages.
+    ///
+    /// Most variants have none, but any grid can be given some with
+    /// [`Constrained`](crate::constraint::Constrained).
+    fn constraints(&self) -> Vec<Arc<dyn Constraint>> {
+        Vec::new()
+    }
+
     /// Check if all cells in the suduko has been filled.
     fn filled(&self) -> bool {
         self.cells().iter().all(|c| c.is_some())
@@ -67,64 +206,230 @@ pub trait Sudoku: Sized + Display {
 
     /// Check if all currently set cells are legal.
     fn legal(&self) -> bool {
+        let cages = self.cages();
+        let cage_cells = cages
+            .iter()
+            .map(|cage| cage.cells.iter().map(|&i| self.get(i)).collect());
+
         let groups = self.groups();
-        groups.into_iter().all(|mut group| {
-            // Check that all cells in group that are set are unique.
-            group.sort();
-            group.windows(2).all(|w| w[0] != w[1] || w[0].is_none())
-        })
+        let unique = groups
+            .into_iter()
+            .chain(cage_cells)
+            .all(|mut group: Vec<Cell>| {
+                // Check that all cells in group that are set are unique.
+                group.sort();
+                group.windows(2).all(|w| w[0] != w[1] || w[0].is_none())
+            });
+
+        unique
+            && cages
+                .iter()
+                .all(|cage| cage.reachable(self.cells(), self.cell_values()))
+            && self
+                .constraints()
+                .iter()
+                .all(|constraint| constraint.check(self.cells()).is_empty())
+    }
+
+    /// Find every pair of set cells that share a group and have the same
+    /// digit.
+    ///
+    /// Cells that share more than one group are reported once for every group
+    /// they clash in. Cages are checked for repeated digits too, but not for
+    /// their sums, and after them come the pairs of cells that break a
+    /// constraint.
+    fn conflicts(&self) -> Vec<Conflict> {
+        let mut conflicts = Vec::new();
+
+        let groups = self.group_indices();
+        let count = groups.len();
+        let cages = self.cages().into_iter().map(|cage| cage.cells);
+        let count_cages = count + cages.len();
+
+        for (group, indices) in groups.into_iter().chain(cages).enumerate() {
+            for (n, &a) in indices.iter().enumerate() {
+                for &b in &indices[n + 1..] {
+                    if self.get(a).is_some() && self.get(a) == self.get(b) {
+                        conflicts.push(Conflict {
+                            cells: (a.min(b), a.max(b)),
+                            group,
+                            kind: match group.checked_sub(count) {
+                                Some(cage) => GroupKind::Cage(cage),
+                                None => self.group_kind(group),
+                            },
+                        });
+                    }
+                }
+            }
+        }
+
+        for (n, constraint) in self.constraints().iter().enumerate() {
+            for (a, b) in constraint.check(self.cells()) {
+                conflicts.push(Conflict {
+                    cells: (a.min(b), a.max(b)),
+                    group: count_cages + n,
+                    kind: GroupKind::Constraint(n),
+                });
+            }
+        }
+
+        conflicts
     }
 
     /// Check if the suduko has been solved.
     fn solved(&self) -> bool {
         let groups = self.groups();
-        groups.into_iter().all(|mut group| {
+        let solved = groups.into_iter().all(|mut group| {
             // Check that both all cells in group are set and that there are no
             // repeating values.
             group.sort();
-            group[0] != None && group.windows(2).all(|w| w[0] != w[1])
-        })
+            group[0].is_some() && group.windows(2).all(|w| w[0] != w[1])
+        });
+
+        // With every cell filled, a cage can only reach the sum its digits add
+        // up to, so the cages and constraints hold if the grid is legal.
+        solved && (self.cages().is_empty() && self.constraints().is_empty() || self.legal())
     }
 
-    /// Solve the suduko
-    fn solve(&mut self) -> Result<(), &'static str> {
-        backtrack(self, 0)
+    /// Solve the suduko.
+    ///
+    /// The solver keeps track of the digits that can still be placed on every
+    /// cell, fills in every cell that is left with a single option, and only
+    /// guesses on the cell with the fewest options once it runs out of forced
+    /// moves.
+    fn solve(&mut self) -> Result<(), SudokuError> {
+        solve_with(self, Search::new(self))
+    }
+
+    /// Solve the suduko, giving up after making `limit` guesses.
+    ///
+    /// Returns [`SudokuError::LimitExceeded`] if no solution was found within
+    /// the limit.
+    fn solve_with_limit(&mut self, limit: usize) -> Result<(), SudokuError> {
+        solve_with(self, Search::new(self).with_limit(limit))
     }
-}
 
-fn backtrack(sudoku: &mut impl Sudoku, pos: usize) -> Result<(), &'static str> {
-    if !sudoku.legal() {
-        return Err("cannot solve illegal position");
+    /// Count the solutions of the suduko, stopping once `limit` solutions have
+    /// been found.
+    ///
+    /// The grid itself is left untouched. Illegal positions have no solutions.
+    fn count_solutions(&self, limit: usize) -> usize {
+        Search::new(self).take(limit).count()
     }
 
-    if pos >= sudoku.cells().len() {
-        return Ok(());
+    /// Check if the suduko has exactly one solution.
+    fn has_unique_solution(&self) -> bool {
+        self.count_solutions(2) == 1
     }
 
-    if sudoku.get(pos).is_some() {
-        return backtrack(sudoku, pos + 1);
+    /// Check if every clue of the suduko is needed, so that it has exactly one
+    /// solution but more than one once any of its clues is taken away.
+    fn is_minimal(&self) -> bool {
+        self.has_unique_solution()
+            && (0..self.cells().len())
+                .filter(|&i| self.get(i).is_some())
+                .all(|i| {
+                    let mut fewer = self.clone();
+                    fewer.set(i, None);
+                    !fewer.has_unique_solution()
+                })
     }
 
-    let illegal = FxHashSet::from_iter(sudoku.groups_of(pos).into_iter().flatten());
-    let possible = sudoku
-        .cell_values()
-        .filter(|&value| !illegal.contains(&Some(value)));
+    /// Take away every clue that isn't needed for the suduko to have exactly
+    /// one solution, going through the cells in order.
+    ///
+    /// Returns [`SudokuError::IllegalPosition`] if the grid breaks the rules,
+    /// and [`SudokuError::Unsolvable`] or [`SudokuError::MultipleSolutions`]
+    /// if it doesn't have exactly one solution to begin with.
+    fn minimize(&mut self) -> Result<(), SudokuError> {
+        let conflicts = self.conflicts();
+        if !conflicts.is_empty() {
+            return Err(SudokuError::IllegalPosition { conflicts });
+        }
 
-    for value in possible {
-        sudoku.set(pos, Some(value));
-        if let Ok(_) = backtrack(sudoku, pos + 1) {
-            return Ok(());
+        match self.count_solutions(2) {
+            0 => return Err(SudokuError::Unsolvable),
+            1 => (),
+            _ => return Err(SudokuError::MultipleSolutions),
         }
+
+        remove_clues(self, (0..self.cells().len()).map(|i| vec![i]));
+        Ok(())
     }
 
-    sudoku.set(pos, None);
+    /// Iterate over every solution of the suduko.
+    ///
+    /// The solutions are found lazily, with each step of the iterator picking
+    /// up the search where the previous solution was found.
+    fn solutions(&self) -> Solutions<Self> {
+        Solutions {
+            sudoku: self.clone(),
+            search: Search::new(self),
+        }
+    }
+}
 
-    Err("suduko cannot be solved")
+/// Take away the clues of each set of cells in `sets`, in order, unless the
+/// suduko would no longer have exactly one solution without them.
+pub(crate) fn remove_clues(sudoku: &mut impl Sudoku, sets: impl IntoIterator<Item = Vec<usize>>) {
+    for cells in sets {
+        let clues = cells.iter().map(|&i| sudoku.get(i)).collect::<Vec<_>>();
+        if clues.iter().all(Option::is_none) {
+            continue;
+        }
+
+        for &i in &cells {
+            sudoku.set(i, None);
+        }
+        if !sudoku.has_unique_solution() {
+            for (&i, &clue) in cells.iter().zip(&clues) {
+                sudoku.set(i, clue);
+            }
+        }
+    }
+}
+
+fn solve_with(sudoku: &mut impl Sudoku, mut search: Search) -> Result<(), SudokuError> {
+    let conflicts = sudoku.conflicts();
+    if !conflicts.is_empty() {
+        return Err(SudokuError::IllegalPosition { conflicts });
+    }
+
+    match search.next() {
+        Some(solution) => {
+            sudoku.cells_mut().copy_from_slice(&solution);
+            Ok(())
+        }
+        None => match search.limit() {
+            Some(limit) if search.exceeded() => Err(SudokuError::LimitExceeded { limit }),
+            _ => Err(SudokuError::Unsolvable),
+        },
+    }
+}
+
+/// An iterator over the solutions of a suduko.
+///
+/// This is created by [`Sudoku::solutions`].
+pub struct Solutions<S> {
+    sudoku: S,
+    search: Search,
+}
+
+impl<S: Sudoku> Iterator for Solutions<S> {
+    type Item = S;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let cells = self.search.next()?;
+
+        let mut solution = self.sudoku.clone();
+        solution.cells_mut().copy_from_slice(&cells);
+        Some(solution)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::variants::StandardSudoku;
+    use crate::variants::{MiniSudoku, StandardSudoku};
     use super::*;
     use std::str::FromStr;
 
@@ -164,4 +469,161 @@ mod tests {
             "762851943354926178819473652931568724245197386678342519497685231126734895583219467"
         );
     }
+
+    #[test]
+    fn solve_evil() {
+        let mut suduko = StandardSudoku::from_str(
+            "  3  7  2  15  79  9      4        9 1   436   5 8    3  4           2   6   317 ",
+        )
+        .unwrap();
+        assert!(suduko.solve().is_ok());
+        assert!(suduko.solved());
+        assert_eq!(suduko.get(2), Some(3));
+        assert_eq!(suduko.get(79), Some(7));
+    }
+
+    #[test]
+    fn solve_impossible() {
+        let mut suduko = StandardSudoku::from_str(
+            "12345678                           9                                             ",
+        )
+        .unwrap();
+        assert!(suduko.legal());
+        assert_eq!(suduko.solve(), Err(SudokuError::Unsolvable));
+
+        let mut suduko = StandardSudoku::from_str(
+            "11                                                                               ",
+        )
+        .unwrap();
+        assert_eq!(
+            suduko.solve(),
+            Err(SudokuError::IllegalPosition {
+                conflicts: vec![
+                    Conflict {
+                        cells: (0, 1),
+                        group: 0,
+                        kind: GroupKind::Row(0),
+                    },
+                    Conflict {
+                        cells: (0, 1),
+                        group: 18,
+                        kind: GroupKind::Grid(0),
+                    },
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn count_solutions() {
+        let suduko = StandardSudoku::from_str(
+            "7 2 519  3 492 1      7 65 931      2    738 67 34  1949768 2 11   3         94 7",
+        )
+        .unwrap();
+        assert_eq!(suduko.count_solutions(10), 1);
+        assert!(suduko.has_unique_solution());
+        assert_eq!(suduko.get(1), None);
+
+        let suduko = StandardSudoku::from_str(
+            "76285194335  2617881  73652931568724245197386678342519497685231126734895583219467",
+        )
+        .unwrap();
+        assert_eq!(suduko.count_solutions(10), 2);
+        assert!(!suduko.has_unique_solution());
+
+        let suduko = StandardSudoku::new();
+        assert_eq!(suduko.count_solutions(5), 5);
+        assert_eq!(suduko.count_solutions(0), 0);
+
+        let suduko = StandardSudoku::from_str(
+            "11                                                                               ",
+        )
+        .unwrap();
+        assert_eq!(suduko.count_solutions(10), 0);
+        assert!(!suduko.has_unique_solution());
+    }
+
+    #[test]
+    fn solutions() {
+        let suduko = StandardSudoku::from_str(
+            "76285194335  2617881  73652931568724245197386678342519497685231126734895583219467",
+        )
+        .unwrap();
+        let solutions = suduko.solutions().collect::<Vec<_>>();
+        assert_eq!(solutions.len(), 2);
+        assert!(solutions.iter().all(|s| s.solved()));
+        assert_ne!(solutions[0], solutions[1]);
+        assert_eq!(suduko.get(11), None);
+
+        let suduko = MiniSudoku::new();
+        let solutions = suduko.solutions().take(100).collect::<Vec<_>>();
+        assert_eq!(solutions.len(), 100);
+        assert!(solutions.iter().all(|s| s.solved()));
+        for (i, a) in solutions.iter().enumerate() {
+            assert!(solutions[i + 1..].iter().all(|b| a != b));
+        }
+    }
+
+    #[test]
+    fn solve_with_limit() {
+        let mut suduko = StandardSudoku::new();
+        assert_eq!(
+            suduko.solve_with_limit(0),
+            Err(SudokuError::LimitExceeded { limit: 0 })
+        );
+        assert!(!suduko.filled());
+
+        assert!(suduko.solve_with_limit(1000).is_ok());
+        assert!(suduko.solved());
+    }
+
+    #[test]
+    fn minimize() {
+        let mut suduko = StandardSudoku::from_str(
+            "7 2 519  3 492 1      7 65 931      2    738 67 34  1949768 2 11   3         94 7",
+        )
+        .unwrap();
+        assert!(!suduko.is_minimal());
+
+        let clues = suduko.cells().iter().filter(|c| c.is_some()).count();
+        assert!(suduko.minimize().is_ok());
+        assert!(suduko.is_minimal());
+        assert!(suduko.cells().iter().filter(|c| c.is_some()).count() < clues);
+
+        let mut solution = suduko;
+        solution.solve().unwrap();
+        assert!(!solution.is_minimal());
+
+        let mut suduko = StandardSudoku::from_str(
+            "76285194335  2617881  73652931568724245197386678342519497685231126734895583219467",
+        )
+        .unwrap();
+        assert!(!suduko.is_minimal());
+        assert_eq!(suduko.minimize(), Err(SudokuError::MultipleSolutions));
+        assert_eq!(suduko.get(0), Some(7));
+    }
+
+    #[test]
+    fn conflicts() {
+        let suduko = StandardSudoku::from_str(
+            "7 2 519  3 492 1      7 65 931      2    738 67 34  1949768 2 11   3         94 7",
+        )
+        .unwrap();
+        assert!(suduko.conflicts().is_empty());
+
+        let suduko = StandardSudoku::from_str(
+            "727 519  3 492 1      7 65 931      2    738 67 34  1949768 2 11   3         94 7",
+        )
+        .unwrap();
+        let conflicts = suduko.conflicts();
+        assert_eq!(
+            conflicts.iter().map(|c| (c.cells, c.kind)).collect::<Vec<_>>(),
+            [
+                ((0, 2), GroupKind::Row(0)),
+                ((2, 56), GroupKind::Column(2)),
+                ((0, 2), GroupKind::Grid(0)),
+            ]
+        );
+        assert_eq!(conflicts[0].to_string(), "cells 0 and 2 clash in row 0");
+    }
 }
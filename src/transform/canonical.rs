@@ -0,0 +1,226 @@
+use crate::sudoku::Sudoku;
+use crate::variants::StandardSudoku;
+
+/// The cells of a grid, with 0 for an empty cell.
+type Grid = [u8; 81];
+
+/// Every ordering of three items.
+const PERMUTATIONS: [[usize; 3]; 6] = [
+    [0, 1, 2],
+    [0, 2, 1],
+    [1, 0, 2],
+    [1, 2, 0],
+    [2, 0, 1],
+    [2, 1, 0],
+];
+
+/// Get every ordering of the rows or columns that keeps the bands or stacks
+/// together.
+fn line_orders() -> Vec<[usize; 9]> {
+    let mut orders = Vec::with_capacity(6 * 6 * 6 * 6);
+    for bands in PERMUTATIONS {
+        for a in PERMUTATIONS {
+            for b in PERMUTATIONS {
+                for c in PERMUTATIONS {
+                    let mut order = [0; 9];
+                    for (i, lines) in [a, b, c].iter().enumerate() {
+                        for (j, line) in lines.iter().enumerate() {
+                            order[i * 3 + j] = bands[i] * 3 + line;
+                        }
+                    }
+                    orders.push(order);
+                }
+            }
+        }
+    }
+    orders
+}
+
+/// Give the digits new labels in the order they first appear, which gives
+/// the lowest sequence of digits for a fixed layout.
+struct Labels {
+    labels: [u8; 10],
+    next: u8,
+}
+
+impl Labels {
+    fn new() -> Self {
+        Self {
+            labels: [0; 10],
+            next: 1,
+        }
+    }
+
+    fn label(&mut self, digit: u8) -> u8 {
+        if digit != 0 && self.labels[digit as usize] == 0 {
+            self.labels[digit as usize] = self.next;
+            self.next += 1;
+        }
+        self.labels[digit as usize]
+    }
+}
+
+/// Lay out `grid` with the rows in `rows` order and the columns in `columns`
+/// order, relabel it, and return it if it comes before `best`.
+///
+/// The comparison stops at the first cell that is higher than in `best`, so
+/// that most layouts are thrown away after a few cells.
+fn layout(grid: &Grid, rows: &[usize], columns: &[usize; 9], best: &Grid) -> Option<Grid> {
+    let mut labels = Labels::new();
+    let mut candidate = [0; 81];
+    let mut lower = false;
+
+    for (r, &row) in rows.iter().enumerate() {
+        for (c, &col) in columns.iter().enumerate() {
+            let i = r * 9 + c;
+            candidate[i] = labels.label(grid[row * 9 + col]);
+            if !lower {
+                match candidate[i].cmp(&best[i]) {
+                    std::cmp::Ordering::Less => lower = true,
+                    std::cmp::Ordering::Greater => return None,
+                    std::cmp::Ordering::Equal => (),
+                }
+            }
+        }
+    }
+
+    Some(candidate)
+}
+
+impl StandardSudoku {
+    /// Get the canonical form of the grid.
+    ///
+    /// This is the grid with the lowest string, counting empty cells as lower
+    /// than any digit, out of every grid that can be made from it by
+    /// transposing it, reordering its bands and stacks, reordering the rows
+    /// of a band or the columns of a stack, and relabelling its digits.
+    /// Turning and flipping the grid are made up of those, so two grids are
+    /// [isomorphic](Self::is_isomorphic) exactly when they have the same
+    /// canonical form.
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use sudoku_solver::transform::{Rotate, Shuffle};
+    /// use sudoku_solver::StandardSudoku;
+    ///
+    /// let game = StandardSudoku::from_str(
+    ///     "  9  2  5538 64  9162    3   3 27    546  1    7 1534 3  8 19 67  3  85  91   47 ",
+    /// )
+    /// .unwrap();
+    /// let other = game.rotate().swap_bands(0, 1);
+    ///
+    /// assert_eq!(game.canonical().to_string(), other.canonical().to_string());
+    /// ```
+    pub fn canonical(&self) -> Self {
+        let grid: Grid = std::array::from_fn(|i| self.get(i).unwrap_or(0));
+        let transposed: Grid = std::array::from_fn(|i| grid[(i % 9) * 9 + i / 9]);
+        let orders = line_orders();
+
+        // The top row only depends on which row goes first and how the
+        // columns are ordered, so find the layouts with the lowest top row
+        // before trying every ordering of the rest of the rows.
+        let mut top = [u8::MAX; 81];
+        let mut starts = Vec::new();
+        for grid in [&grid, &transposed] {
+            for row in 0..9 {
+                for columns in &orders {
+                    let Some(candidate) = layout(grid, &[row], columns, &top) else {
+                        continue;
+                    };
+                    if candidate[..9] < top[..9] {
+                        top[..9].copy_from_slice(&candidate[..9]);
+                        starts.clear();
+                    }
+                    starts.push((grid, row, columns));
+                }
+            }
+        }
+
+        let mut best = [u8::MAX; 81];
+        for (grid, row, columns) in starts {
+            for rows in orders.iter().filter(|rows| rows[0] == row) {
+                if let Some(candidate) = layout(grid, rows, columns, &best) {
+                    best = candidate;
+                }
+            }
+        }
+
+        let mut canonical = Self::new();
+        for (cell, &digit) in canonical.cells_mut().iter_mut().zip(&best) {
+            *cell = (digit != 0).then_some(digit);
+        }
+        canonical
+    }
+
+    /// Check if `other` can be made from this grid by transforming it in a way
+    /// that keeps every valid grid valid.
+    pub fn is_isomorphic(&self, other: &Self) -> bool {
+        self.canonical() == other.canonical()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transform::{Rotate, Shuffle, Transform};
+    use std::str::FromStr;
+
+    const PUZZLE: &str =
+        "1 6    7448 6 1      94   6     923      3   329716   8       367 3   9  35 64  2";
+
+    #[test]
+    fn line_orders() {
+        let orders = super::line_orders();
+        assert_eq!(orders.len(), 1296);
+        assert_eq!(orders[0], [0, 1, 2, 3, 4, 5, 6, 7, 8]);
+        assert!(orders.iter().all(|order| {
+            let mut sorted = *order;
+            sorted.sort_unstable();
+            sorted == [0, 1, 2, 3, 4, 5, 6, 7, 8]
+        }));
+    }
+
+    #[test]
+    fn canonical() {
+        let game = StandardSudoku::from_str(PUZZLE).unwrap();
+        let canonical = game.canonical();
+
+        assert!(canonical.to_string() <= game.to_string());
+        assert_eq!(canonical.canonical(), canonical);
+        assert!(canonical.has_unique_solution());
+        assert_eq!(
+            canonical.cells().iter().filter(|c| c.is_some()).count(),
+            game.cells().iter().filter(|c| c.is_some()).count()
+        );
+
+        for other in [
+            game.rotate(),
+            game.transpose(),
+            game.reflect_horizontal(),
+            game.rotate_180().swap_rows(3, 5).swap_stacks(0, 2),
+            game.swap_bands(1, 2).swap_columns(0, 1),
+            game.relabel(&[3, 1, 4, 5, 9, 2, 6, 8, 7]).anti_transpose(),
+        ] {
+            assert_eq!(other.canonical(), canonical);
+            assert!(game.is_isomorphic(&other));
+        }
+    }
+
+    #[test]
+    fn not_isomorphic() {
+        let game = StandardSudoku::from_str(PUZZLE).unwrap();
+
+        let mut other = game;
+        other.set(1, Some(2));
+        assert!(!game.is_isomorphic(&other));
+
+        // Moving a row out of its band breaks the subgrids.
+        let mut moved = StandardSudoku::new();
+        for i in 0..81 {
+            let (r, c) = (i / 9, i % 9);
+            let r = [0, 1, 3, 2, 4, 5, 6, 7, 8][r];
+            moved.set(r * 9 + c, game.get(i));
+        }
+        assert!(!game.is_isomorphic(&moved));
+    }
+}
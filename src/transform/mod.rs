@@ -0,0 +1,369 @@
+//! Turning a puzzle into an equivalent one.
+//!
+//! Every transformation here turns a valid grid into another valid grid, and
+//! a puzzle into one with the same number of solutions, which are transformed
+//! the same way. Which ones are offered depends on the shape of the variant:
+//!
+//! - [`Transform`] is offered by every variant: relabelling the digits, turning
+//!   the grid half a turn, and flipping it.
+//! - [`Rotate`] is offered by variants where a quarter turn maps every group
+//!   onto another group, which needs square subgrids.
+//! - [`Shuffle`] is offered by variants without groups beyond rows, columns
+//!   and subgrids, where rows can be swapped inside a band of subgrids and
+//!   columns inside a stack of them.
+//!
+//! Standard grids also have a [canonical form](crate::variants::StandardSudoku::canonical),
+//! which is the same for every grid that can be turned into each other.
+
+mod canonical;
+
+use crate::sudoku::Sudoku;
+use crate::variants::{BoxSudoku, DiagonalSudoku, HyperSudoku};
+
+/// Get the number of rows and columns of the grid.
+fn size(sudoku: &impl Sudoku) -> usize {
+    sudoku.rows().len()
+}
+
+/// Get the height and width of the first subgrid.
+fn grid_shape(sudoku: &impl Sudoku) -> (usize, usize) {
+    let n = size(sudoku);
+    let grid = &sudoku.group_indices()[2 * n];
+
+    let mut rows = grid.iter().map(|i| i / n).collect::<Vec<_>>();
+    rows.dedup();
+    (rows.len(), grid.len() / rows.len())
+}
+
+/// Create a grid where the cell on row `r` and column `c` is taken from the
+/// cell on `source(r, c)`.
+fn remap<S: Sudoku>(sudoku: &S, source: impl Fn(usize, usize) -> (usize, usize)) -> S {
+    let n = size(sudoku);
+    let mut remapped = sudoku.clone();
+
+    for (i, cell) in remapped.cells_mut().iter_mut().enumerate() {
+        let (r, c) = source(i / n, i % n);
+        *cell = sudoku.get(r * n + c);
+    }
+
+    remapped
+}
+
+/// Get the index of `i` after swapping `a` and `b`.
+fn swapped(i: usize, a: usize, b: usize) -> usize {
+    match i {
+        i if i == a => b,
+        i if i == b => a,
+        i => i,
+    }
+}
+
+/// Transformations that every variant supports.
+pub trait Transform: Sudoku {
+    /// Replace every digit `d` with `digits[d - first]`, where `first` is the
+    /// lowest value a cell can have.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `digits` is not an ordering of [`Sudoku::cell_values`].
+    fn relabel(&self, digits: &[u8]) -> Self {
+        let values = self.cell_values();
+        let mut sorted = digits.to_vec();
+        sorted.sort_unstable();
+        if !sorted.iter().copied().eq(values.clone()) {
+            panic!("{digits:?} is not an ordering of the digits {values:?}");
+        }
+
+        let mut relabelled = self.clone();
+        for cell in relabelled.cells_mut() {
+            *cell = cell.map(|d| digits[(d - values.start()) as usize]);
+        }
+        relabelled
+    }
+
+    /// Turn the grid half a turn.
+    fn rotate_180(&self) -> Self {
+        let m = size(self) - 1;
+        remap(self, |r, c| (m - r, m - c))
+    }
+
+    /// Flip the grid upside down.
+    fn reflect_horizontal(&self) -> Self {
+        let m = size(self) - 1;
+        remap(self, |r, c| (m - r, c))
+    }
+
+    /// Flip the grid left to right.
+    fn reflect_vertical(&self) -> Self {
+        let m = size(self) - 1;
+        remap(self, |r, c| (r, m - c))
+    }
+}
+
+/// Transformations for variants with square subgrids.
+pub trait Rotate: Transform {
+    /// Turn the grid a quarter turn clockwise.
+    fn rotate(&self) -> Self {
+        let m = size(self) - 1;
+        remap(self, |r, c| (m - c, r))
+    }
+
+    /// Mirror the grid along the diagonal from the top left to the bottom
+    /// right, so that rows become columns.
+    fn transpose(&self) -> Self {
+        remap(self, |r, c| (c, r))
+    }
+
+    /// Mirror the grid along the diagonal from the top right to the bottom
+    /// left.
+    fn anti_transpose(&self) -> Self {
+        let m = size(self) - 1;
+        remap(self, |r, c| (m - c, m - r))
+    }
+}
+
+/// Transformations that move rows and columns around without splitting the
+/// subgrids.
+///
+/// A band is a row of subgrids, and a stack is a column of them, both counted
+/// from zero.
+pub trait Shuffle: Transform {
+    /// Swap rows `a` and `b`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either row is not on the grid, or if the rows are not in
+    /// the same band.
+    fn swap_rows(&self, a: usize, b: usize) -> Self {
+        let (height, _) = grid_shape(self);
+        let rows = size(self);
+        if a.max(b) >= rows {
+            panic!("rows {a} and {b} are not both among the {rows} rows");
+        }
+        if a / height != b / height {
+            panic!("rows {a} and {b} are not in the same band");
+        }
+
+        remap(self, |r, c| (swapped(r, a, b), c))
+    }
+
+    /// Swap columns `a` and `b`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either column is not on the grid, or if the columns are not
+    /// in the same stack.
+    fn swap_columns(&self, a: usize, b: usize) -> Self {
+        let (_, width) = grid_shape(self);
+        let columns = size(self);
+        if a.max(b) >= columns {
+            panic!("columns {a} and {b} are not both among the {columns} columns");
+        }
+        if a / width != b / width {
+            panic!("columns {a} and {b} are not in the same stack");
+        }
+
+        remap(self, |r, c| (r, swapped(c, a, b)))
+    }
+
+    /// Swap bands `a` and `b`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either band is not on the grid.
+    fn swap_bands(&self, a: usize, b: usize) -> Self {
+        let (height, _) = grid_shape(self);
+        let bands = size(self) / height;
+        if a.max(b) >= bands {
+            panic!("bands {a} and {b} are not both among the {bands} bands");
+        }
+        remap(self, |r, c| {
+            (swapped(r / height, a, b) * height + r % height, c)
+        })
+    }
+
+    /// Swap stacks `a` and `b`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either stack is not on the grid.
+    fn swap_stacks(&self, a: usize, b: usize) -> Self {
+        let (_, width) = grid_shape(self);
+        let stacks = size(self) / width;
+        if a.max(b) >= stacks {
+            panic!("stacks {a} and {b} are not both among the {stacks} stacks");
+        }
+        remap(self, |r, c| {
+            (r, swapped(c / width, a, b) * width + c % width)
+        })
+    }
+}
+
+impl<const W: usize, const H: usize, const N: usize> Transform for BoxSudoku<W, H, N> {}
+impl<const S: usize, const N: usize> Rotate for BoxSudoku<S, S, N> {}
+impl<const W: usize, const H: usize, const N: usize> Shuffle for BoxSudoku<W, H, N> {}
+
+impl Transform for HyperSudoku {}
+impl Rotate for HyperSudoku {}
+
+impl Transform for DiagonalSudoku {}
+impl Rotate for DiagonalSudoku {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variants::{MiniSudoku, StandardSudoku};
+    use std::str::FromStr;
+
+    const SOLUTION: &str =
+        "827154396965327148341689752593468271472513689618972435786235914154796823239841567";
+
+    fn standard() -> StandardSudoku {
+        StandardSudoku::from_str(SOLUTION).unwrap()
+    }
+
+    #[test]
+    fn geometry() {
+        let game = standard();
+
+        let rotated = game.rotate();
+        assert!(rotated.solved());
+        assert_eq!(rotated.get(0), game.get(72));
+        assert_eq!(rotated.get(8), game.get(0));
+        assert_eq!(rotated.rotate(), game.rotate_180());
+        assert_eq!(rotated.rotate().rotate().rotate(), game);
+
+        assert_eq!(game.transpose().get(1), game.get(9));
+        assert_eq!(game.anti_transpose().get(0), game.get(80));
+        assert_eq!(game.reflect_horizontal().get(0), game.get(72));
+        assert_eq!(game.reflect_vertical().get(0), game.get(8));
+        assert_eq!(game.transpose().reflect_vertical(), rotated);
+
+        for transformed in [
+            game.transpose(),
+            game.anti_transpose(),
+            game.reflect_horizontal(),
+            game.reflect_vertical(),
+        ] {
+            assert!(transformed.solved());
+        }
+    }
+
+    #[test]
+    fn relabel() {
+        let game = standard();
+        let relabelled = game.relabel(&[9, 8, 7, 6, 5, 4, 3, 2, 1]);
+        assert!(relabelled.solved());
+        assert_eq!(relabelled.get(0), Some(2));
+    }
+
+    #[test]
+    #[should_panic]
+    fn relabel_repeated() {
+        standard().relabel(&[1, 1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn shuffle() {
+        let game = standard();
+
+        let swapped = game.swap_rows(0, 2);
+        assert!(swapped.solved());
+        assert_eq!(swapped.get(0), game.get(18));
+
+        let swapped = game.swap_columns(3, 5);
+        assert!(swapped.solved());
+        assert_eq!(swapped.get(3), game.get(5));
+
+        let swapped = game.swap_bands(0, 2);
+        assert!(swapped.solved());
+        assert_eq!(swapped.get(9), game.get(63));
+
+        let swapped = game.swap_stacks(1, 2);
+        assert!(swapped.solved());
+        assert_eq!(swapped.get(4), game.get(7));
+    }
+
+    #[test]
+    #[should_panic]
+    fn swap_rows_across_bands() {
+        standard().swap_rows(2, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "rows 9 and 10 are not both among the 9 rows")]
+    fn swap_rows_off_the_grid() {
+        standard().swap_rows(9, 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "columns 4 and 12 are not both among the 9 columns")]
+    fn swap_columns_off_the_grid() {
+        standard().swap_columns(4, 12);
+    }
+
+    #[test]
+    #[should_panic(expected = "bands 1 and 3 are not both among the 3 bands")]
+    fn swap_bands_off_the_grid() {
+        standard().swap_bands(1, 3);
+    }
+
+    #[test]
+    fn variants() {
+        let mini = MiniSudoku::from_str("123456456123231564564231312645645312").unwrap();
+        assert!(mini.solved());
+        for transformed in [
+            mini.rotate_180(),
+            mini.reflect_horizontal(),
+            mini.reflect_vertical(),
+            mini.swap_rows(0, 1),
+            mini.swap_columns(3, 5),
+            mini.swap_bands(0, 2),
+            mini.swap_stacks(0, 1),
+            mini.relabel(&[6, 5, 4, 3, 2, 1]),
+        ] {
+            assert!(transformed.solved());
+        }
+
+        let mut eight = BoxSudoku::<4, 2, 64>::new();
+        eight.solve().unwrap();
+        for transformed in [
+            eight.swap_rows(6, 7),
+            eight.swap_bands(0, 3),
+            eight.swap_stacks(0, 1),
+        ] {
+            assert!(transformed.solved());
+        }
+
+        let mut sixteen = BoxSudoku::<4, 4, 256>::new();
+        sixteen.solve().unwrap();
+        assert!(sixteen.rotate().swap_columns(4, 7).solved());
+
+        let mut hyper = HyperSudoku::from_str(
+            "       1   2    34    51        65   7 3   8   3          8    58    9  69       ",
+        )
+        .unwrap();
+        hyper.solve().unwrap();
+        for transformed in [
+            hyper.rotate(),
+            hyper.transpose(),
+            hyper.anti_transpose(),
+            hyper.rotate_180(),
+            hyper.reflect_horizontal(),
+            hyper.reflect_vertical(),
+        ] {
+            assert!(transformed.solved());
+        }
+
+        let mut diagonal = DiagonalSudoku::new();
+        diagonal.solve().unwrap();
+        for transformed in [
+            diagonal.rotate(),
+            diagonal.transpose(),
+            diagonal.anti_transpose(),
+            diagonal.reflect_vertical(),
+        ] {
+            assert!(transformed.solved());
+        }
+    }
+}
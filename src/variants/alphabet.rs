@@ -0,0 +1,156 @@
+use crate::error::SudokuError;
+
+/// The symbols used to write the digits of a grid.
+///
+/// The first symbol stands for the digit 1, the second one for 2, and so on.
+/// Grids with more than nine digits cannot be written with the digits 1 to 9
+/// alone, and puzzles with them are written in a few different ways, which
+/// are all available here.
+///
+/// ```
+/// use sudoku_solver::variants::{Alphabet, BoxSudoku};
+///
+/// let alphabet = Alphabet::hexadecimal();
+/// assert_eq!(alphabet.symbol(1), Some('0'));
+/// assert_eq!(alphabet.digit('F'), Some(16));
+///
+/// let game = BoxSudoku::<2, 2, 16>::from_str_with("ABCD            ", &Alphabet::letters()).unwrap();
+/// assert_eq!(game.to_string(), "1234            ");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Alphabet {
+    symbols: Vec<char>,
+}
+
+impl Alphabet {
+    /// Create an alphabet from its symbols, in the order of the digits they
+    /// stand for.
+    ///
+    /// Every symbol must be different, and none of them can be a space or a
+    /// line break, which are used to write empty cells and rows.
+    pub fn new(symbols: &str) -> Result<Self, SudokuError> {
+        let symbols = symbols.chars().collect::<Vec<_>>();
+
+        for (position, &character) in symbols.iter().enumerate() {
+            if character.is_whitespace() || symbols[..position].contains(&character) {
+                return Err(SudokuError::InvalidCharacter {
+                    position,
+                    character,
+                });
+            }
+        }
+
+        Ok(Self { symbols })
+    }
+
+    /// The digits from 1 to 9 followed by the letters from `A` to `Z`.
+    ///
+    /// This is the alphabet used by [`FromStr`](std::str::FromStr) and
+    /// [`Display`](std::fmt::Display), which makes a 16x16 grid use the
+    /// symbols from 1 to 9 and then `A` to `G`.
+    pub fn alphanumeric() -> Self {
+        Self {
+            symbols: ('1'..='9').chain('A'..='Z').collect(),
+        }
+    }
+
+    /// The digits from 0 to 9 followed by the letters from `A` to `F`, for
+    /// 16x16 grids.
+    pub fn hexadecimal() -> Self {
+        Self {
+            symbols: ('0'..='9').chain('A'..='F').collect(),
+        }
+    }
+
+    /// The letters from `A` to `Z`, which make a 25x25 grid use the symbols
+    /// from `A` to `Y`.
+    pub fn letters() -> Self {
+        Self {
+            symbols: ('A'..='Z').collect(),
+        }
+    }
+
+    /// Get the number of digits that can be written with the alphabet.
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Check if the alphabet has no symbols.
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// Get the symbol that stands for `digit`.
+    pub fn symbol(&self, digit: u8) -> Option<char> {
+        (digit as usize)
+            .checked_sub(1)
+            .and_then(|i| self.symbols.get(i))
+            .copied()
+    }
+
+    /// Get the digit that `symbol` stands for.
+    ///
+    /// Letters are matched regardless of their case, unless the alphabet has
+    /// symbols for both cases.
+    pub fn digit(&self, symbol: char) -> Option<u8> {
+        let position = |symbol| self.symbols.iter().position(|&s| s == symbol);
+
+        position(symbol)
+            .or_else(|| position(symbol.to_ascii_uppercase()))
+            .or_else(|| position(symbol.to_ascii_lowercase()))
+            .map(|i| i as u8 + 1)
+    }
+}
+
+impl Default for Alphabet {
+    fn default() -> Self {
+        Self::alphanumeric()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symbols() {
+        let alphanumeric = Alphabet::alphanumeric();
+        assert_eq!(alphanumeric.symbol(1), Some('1'));
+        assert_eq!(alphanumeric.symbol(16), Some('G'));
+        assert_eq!(alphanumeric.symbol(0), None);
+        assert_eq!(alphanumeric.digit('9'), Some(9));
+        assert_eq!(alphanumeric.digit('a'), Some(10));
+        assert_eq!(alphanumeric.digit('0'), None);
+
+        let hexadecimal = Alphabet::hexadecimal();
+        assert_eq!(hexadecimal.len(), 16);
+        assert_eq!(hexadecimal.digit('0'), Some(1));
+        assert_eq!(hexadecimal.symbol(16), Some('F'));
+        assert_eq!(hexadecimal.digit('G'), None);
+
+        let letters = Alphabet::letters();
+        assert_eq!(letters.digit('A'), Some(1));
+        assert_eq!(letters.symbol(25), Some('Y'));
+
+        let custom = Alphabet::new("aA").unwrap();
+        assert_eq!(custom.digit('A'), Some(2));
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            Alphabet::new("ABCA"),
+            Err(SudokuError::InvalidCharacter {
+                position: 3,
+                character: 'A'
+            })
+        );
+        assert_eq!(
+            Alphabet::new("AB C"),
+            Err(SudokuError::InvalidCharacter {
+                position: 2,
+                character: ' '
+            })
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use super::parse_cells;
+use crate::error::SudokuError;
+use crate::sudoku::{Cell, GroupKind, Sudoku};
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// Diagonal Sudoku, also known as Sudoku X.
+///
+/// Played on the same 9x9 grid as standard Sudoku with all the same rules, but
+/// the two main diagonals must also have every digit once.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiagonalSudoku {
+    cells: [Cell; 9 * 9],
+}
+
+impl DiagonalSudoku {
+    /// Create a new diagonal Sudoku grid.
+    pub fn new() -> Self {
+        Self {
+            cells: [None; 9 * 9],
+        }
+    }
+
+    fn grid(&self, i: usize) -> Vec<Cell> {
+        Self::grid_indices(i).map(|i| self.cells[i]).collect()
+    }
+
+    fn grid_indices(i: usize) -> impl Iterator<Item = usize> {
+        let offset = (i / 3) * 9 * 3 + (i % 3) * 3;
+        (0..9).map(move |j| offset + (j / 3) * 9 + j % 3)
+    }
+
+    fn diagonal(&self, i: usize) -> Vec<Cell> {
+        Self::diagonal_indices(i).map(|i| self.cells[i]).collect()
+    }
+
+    /// Get the cells of the diagonal from the top left corner for `i == 0`,
+    /// or from the top right corner for `i == 1`, from top to bottom.
+    fn diagonal_indices(i: usize) -> impl Iterator<Item = usize> {
+        (0..9).map(move |row| match i {
+            0 => row * 9 + row,
+            1 => row * 9 + 8 - row,
+            _ => unreachable!("{i}"),
+        })
+    }
+}
+
+impl Default for DiagonalSudoku {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sudoku for DiagonalSudoku {
+    fn get(&self, i: usize) -> Cell {
+        self.cells[i]
+    }
+
+    fn set(&mut self, i: usize, num: Cell) {
+        if let Some(num) = num {
+            if !self.cell_values().contains(&num) {
+                panic!("{num} is not a valid value for this cell");
+            }
+        }
+
+        self.cells[i] = num;
+    }
+
+    fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut [Cell] {
+        &mut self.cells
+    }
+
+    fn cell_values(&self) -> RangeInclusive<u8> {
+        1..=9
+    }
+
+    fn rows(&self) -> Vec<Vec<Cell>> {
+        self.cells.chunks_exact(9).map(|c| c.to_vec()).collect()
+    }
+
+    fn columns(&self) -> Vec<Vec<Cell>> {
+        (0..9)
+            .map(|i| self.cells.into_iter().skip(i).step_by(9).collect())
+            .collect()
+    }
+
+    fn grids(&self) -> Vec<Vec<Cell>> {
+        (0..9).map(|i| self.grid(i)).collect()
+    }
+
+    fn group_indices(&self) -> Vec<Vec<usize>> {
+        let rows = (0..9).map(|row| (row * 9..row * 9 + 9).collect());
+        let columns = (0..9).map(|col| (col..9 * 9).step_by(9).collect());
+        let grids = (0..9).map(|i| Self::grid_indices(i).collect());
+        let diagonals = (0..2).map(|i| Self::diagonal_indices(i).collect());
+
+        rows.chain(columns).chain(grids).chain(diagonals).collect()
+    }
+
+    fn group_kind(&self, group: usize) -> GroupKind {
+        match group {
+            0..=8 => GroupKind::Row(group),
+            9..=17 => GroupKind::Column(group - 9),
+            18..=26 => GroupKind::Grid(group - 18),
+            _ => GroupKind::Diagonal(group - 27),
+        }
+    }
+
+    fn groups_of(&self, i: usize) -> Vec<Vec<Cell>> {
+        let mut v = Vec::with_capacity(5);
+
+        let row = i / 9;
+        let col = i % 9;
+        let group = (row / 3) * 3 + (col / 3);
+
+        v.push(self.cells.into_iter().skip(row * 9).take(9).collect());
+        v.push(self.cells.into_iter().skip(col).step_by(9).collect());
+        v.push(self.grid(group));
+
+        if row == col {
+            v.push(self.diagonal(0));
+        }
+        if row + col == 8 {
+            v.push(self.diagonal(1));
+        }
+
+        v
+    }
+
+    fn groups(&self) -> Vec<Vec<Cell>> {
+        let mut v = Vec::with_capacity(9 * 3 + 2);
+        v.append(&mut self.rows());
+        v.append(&mut self.columns());
+        v.append(&mut self.grids());
+        v.push(self.diagonal(0));
+        v.push(self.diagonal(1));
+        v
+    }
+}
+
+impl Display for DiagonalSudoku {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(
+            &self
+                .cells
+                .iter()
+                .map(|c| match c {
+                    Some(digit) => (b'0' + *digit) as char,
+                    None => ' ',
+                })
+                .collect::<String>(),
+        )
+    }
+}
+
+impl FromStr for DiagonalSudoku {
+    type Err = SudokuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut game = Self::new();
+        let cells = parse_cells(s, game.cell_values(), game.cells.len())?;
+        game.cells.copy_from_slice(&cells);
+
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::LogicalSolver;
+    use crate::variants::StandardSudoku;
+
+    const PUZZLE: &str =
+        "2   16    18     6  798     21   8      4   23   2 1     2 5  7 7 86  9      4   ";
+
+    #[test]
+    fn groups() {
+        let game = DiagonalSudoku::from_str(PUZZLE).unwrap();
+
+        assert_eq!(
+            game.diagonal(0),
+            [
+                Some(2),
+                Some(1),
+                Some(7),
+                None,
+                Some(4),
+                None,
+                None,
+                Some(9),
+                None
+            ]
+        );
+        assert_eq!(
+            game.diagonal(1),
+            [None, None, None, None, Some(4), None, None, Some(7), None]
+        );
+        assert_eq!(game.grids()[0], game.grid(0));
+        assert_eq!(game.groups().len(), 9 * 3 + 2);
+    }
+
+    #[test]
+    fn groups_of() {
+        let game = DiagonalSudoku::from_str(PUZZLE).unwrap();
+
+        assert_eq!(game.groups_of(1).len(), 3);
+        assert_eq!(game.groups_of(0)[3], game.diagonal(0));
+        assert_eq!(game.groups_of(8)[3], game.diagonal(1));
+        assert_eq!(game.groups_of(9 * 7 + 1)[3], game.diagonal(1));
+        assert_eq!(game.groups_of(40).len(), 5);
+        assert_eq!(game.groups_of(40)[4], game.diagonal(1));
+    }
+
+    #[test]
+    fn group_indices() {
+        let game = DiagonalSudoku::from_str(PUZZLE).unwrap();
+
+        let groups = game
+            .group_indices()
+            .into_iter()
+            .map(|group| group.into_iter().map(|i| game.get(i)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(groups, game.groups());
+    }
+
+    #[test]
+    fn solve() {
+        let mut game = DiagonalSudoku::from_str(PUZZLE).unwrap();
+
+        // Without the diagonals the puzzle has more than one solution.
+        assert_eq!(
+            StandardSudoku::from_str(PUZZLE).unwrap().count_solutions(2),
+            2
+        );
+
+        let mut solver = LogicalSolver::new(&game).unwrap();
+        solver.solve();
+        assert!(solver.solved());
+
+        assert!(game.solve().is_ok());
+        assert!(game.solved());
+        assert_eq!(solver.cells(), game.cells());
+        assert_eq!(
+            game.to_string(),
+            "295416738418573926637982541721359864986147352354628179843295617572861493169734285"
+        );
+    }
+
+    #[test]
+    fn conflicts() {
+        let mut game = DiagonalSudoku::new();
+        game.set(8, Some(4));
+        game.set(9 * 8, Some(4));
+
+        let conflicts = game.conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].cells, (8, 72));
+        assert_eq!(conflicts[0].group, 28);
+        assert_eq!(conflicts[0].kind, GroupKind::Diagonal(1));
+    }
+}
@@ -0,0 +1,307 @@
+use super::{parse_cells_with, Alphabet};
+use crate::error::SudokuError;
+use crate::sudoku::{Cell, Sudoku};
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// Sudoku on a grid of any size with rectangular subgrids.
+///
+/// Every subgrid is `W` cells wide and `H` cells tall, which makes the grid
+/// `W * H` cells along each side, played with the digits from 1 to `W * H`.
+/// Digits above 9 are written as letters, starting with `A` for 10, unless
+/// the grid is read or written with another [`Alphabet`].
+///
+/// The cells are kept inline so that grids can be copied, which needs their
+/// number as `N`, and it must be `(W * H)²`.
+///
+/// | Grid    | Type                   |
+/// |---------|------------------------|
+/// | 4x4     | `BoxSudoku<2, 2, 16>`  |
+/// | 6x6     | [`MiniSudoku`]         |
+/// | 8x8     | `BoxSudoku<4, 2, 64>`  |
+/// | 9x9     | [`StandardSudoku`]     |
+/// | 12x12   | `BoxSudoku<4, 3, 144>` |
+/// | 16x16   | `BoxSudoku<4, 4, 256>` |
+/// | 25x25   | `BoxSudoku<5, 5, 625>` |
+///
+/// Grids can have at most 31 digits, so that the solver can keep the
+/// candidates of a cell in a single bitmask. Larger grids don't compile, nor
+/// do grids with the wrong number of cells:
+///
+/// ```compile_fail
+/// use sudoku_solver::variants::BoxSudoku;
+///
+/// let game = BoxSudoku::<4, 8, 1024>::new();
+/// ```
+///
+/// ```compile_fail
+/// use sudoku_solver::variants::BoxSudoku;
+///
+/// let game = BoxSudoku::<3, 3, 80>::new();
+/// ```
+///
+/// [`MiniSudoku`]: super::MiniSudoku
+/// [`StandardSudoku`]: super::StandardSudoku
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BoxSudoku<const W: usize, const H: usize, const N: usize> {
+    cells: [Cell; N],
+}
+
+impl<const W: usize, const H: usize, const N: usize> BoxSudoku<W, H, N> {
+    /// The number of cells along each side of the grid.
+    const SIZE: usize = W * H;
+
+    /// Stops grids with more digits than the candidates can hold, or with the
+    /// wrong number of cells, from compiling.
+    const FITS: () = {
+        assert!(W * H <= 31, "a grid can have at most 31 digits");
+        assert!(N == W * H * W * H, "a grid must have (W * H)² cells");
+    };
+
+    /// Create an empty grid.
+    pub fn new() -> Self {
+        let () = Self::FITS;
+        Self { cells: [None; N] }
+    }
+
+    pub(super) fn row(&self, i: usize) -> Vec<Cell> {
+        self.cells[i * Self::SIZE..(i + 1) * Self::SIZE].to_vec()
+    }
+
+    pub(super) fn column(&self, i: usize) -> Vec<Cell> {
+        self.cells
+            .iter()
+            .skip(i)
+            .step_by(Self::SIZE)
+            .copied()
+            .collect()
+    }
+
+    pub(super) fn grid(&self, i: usize) -> Vec<Cell> {
+        Self::grid_indices(i).map(|i| self.cells[i]).collect()
+    }
+
+    /// Read a grid written with the symbols of `alphabet`.
+    ///
+    /// ```
+    /// use sudoku_solver::variants::{Alphabet, BoxSudoku};
+    ///
+    /// let row = format!("0123456789ABCDEF{}", " ".repeat(15 * 16));
+    /// let game = BoxSudoku::<4, 4, 256>::from_str_with(&row, &Alphabet::hexadecimal()).unwrap();
+    /// assert_eq!(game.to_string()[..16], *"123456789ABCDEFG");
+    /// ```
+    pub fn from_str_with(s: &str, alphabet: &Alphabet) -> Result<Self, SudokuError> {
+        let mut game = Self::new();
+        let cells = parse_cells_with(s, alphabet, game.cell_values(), game.cells.len())?;
+        game.cells.copy_from_slice(&cells);
+
+        Ok(game)
+    }
+
+    /// Write the grid with the symbols of `alphabet`, in the same layout as
+    /// [`Display`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alphabet` has no symbol for some digit on the grid.
+    pub fn to_string_with(&self, alphabet: &Alphabet) -> String {
+        self.cells
+            .iter()
+            .map(|c| match c {
+                Some(digit) => alphabet
+                    .symbol(*digit)
+                    .unwrap_or_else(|| panic!("the alphabet has no symbol for {digit}")),
+                None => ' ',
+            })
+            .collect()
+    }
+
+    fn grid_indices(i: usize) -> impl Iterator<Item = usize> {
+        let offset = (i / H) * H * Self::SIZE + (i % H) * W;
+        (0..Self::SIZE).map(move |j| offset + (j / W) * Self::SIZE + j % W)
+    }
+}
+
+impl<const W: usize, const H: usize, const N: usize> Default for BoxSudoku<W, H, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: usize, const H: usize, const N: usize> Sudoku for BoxSudoku<W, H, N> {
+    fn get(&self, i: usize) -> Cell {
+        self.cells[i]
+    }
+
+    fn set(&mut self, i: usize, num: Cell) {
+        if let Some(num) = num {
+            if !self.cell_values().contains(&num) {
+                panic!("{num} is not a valid value for this cell");
+            }
+        }
+
+        self.cells[i] = num;
+    }
+
+    fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut [Cell] {
+        &mut self.cells
+    }
+
+    fn cell_values(&self) -> RangeInclusive<u8> {
+        1..=Self::SIZE as u8
+    }
+
+    fn rows(&self) -> Vec<Vec<Cell>> {
+        (0..Self::SIZE).map(|i| self.row(i)).collect()
+    }
+
+    fn columns(&self) -> Vec<Vec<Cell>> {
+        (0..Self::SIZE).map(|i| self.column(i)).collect()
+    }
+
+    fn grids(&self) -> Vec<Vec<Cell>> {
+        (0..Self::SIZE).map(|i| self.grid(i)).collect()
+    }
+
+    fn group_indices(&self) -> Vec<Vec<usize>> {
+        let n = Self::SIZE;
+        let rows = (0..n).map(|row| (row * n..row * n + n).collect());
+        let columns = (0..n).map(|col| (col..n * n).step_by(n).collect());
+        let grids = (0..n).map(|i| Self::grid_indices(i).collect());
+
+        rows.chain(columns).chain(grids).collect()
+    }
+
+    fn groups_of(&self, i: usize) -> Vec<Vec<Cell>> {
+        let row = i / Self::SIZE;
+        let col = i % Self::SIZE;
+        let group = (row / H) * H + col / W;
+
+        [self.row(row), self.column(col), self.grid(group)].into()
+    }
+}
+
+impl<const W: usize, const H: usize, const N: usize> Display for BoxSudoku<W, H, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_string_with(&Alphabet::alphanumeric()))
+    }
+}
+
+impl<const W: usize, const H: usize, const N: usize> FromStr for BoxSudoku<W, H, N> {
+    type Err = SudokuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_str_with(s, &Alphabet::alphanumeric())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_indices() {
+        let game = BoxSudoku::<4, 2, 64>::new();
+        let groups = game.group_indices();
+        assert_eq!(groups.len(), 3 * 8);
+        assert_eq!(groups[16], [0, 1, 2, 3, 8, 9, 10, 11]);
+        assert_eq!(groups[17], [4, 5, 6, 7, 12, 13, 14, 15]);
+        assert_eq!(groups[18], [16, 17, 18, 19, 24, 25, 26, 27]);
+
+        let game = BoxSudoku::<4, 3, 144>::new();
+        assert_eq!(game.groups_of(12 * 4 + 5)[2], game.grid(4));
+    }
+
+    #[test]
+    fn solve() {
+        let mut game = BoxSudoku::<2, 2, 16>::from_str("1    2    3    4").unwrap();
+        assert!(game.solve().is_ok());
+        assert!(game.solved());
+
+        let mut game = BoxSudoku::<4, 2, 64>::new();
+        assert!(game.solve().is_ok());
+        assert!(game.solved());
+
+        let mut game = BoxSudoku::<4, 3, 144>::new();
+        assert!(game.solve().is_ok());
+        assert!(game.solved());
+        assert!(game.to_string().contains('C'));
+
+        let mut game = BoxSudoku::<5, 5, 625>::new();
+        assert!(game.solve().is_ok());
+        assert!(game.solved());
+    }
+
+    #[test]
+    fn letters() {
+        let mut game = BoxSudoku::<4, 4, 256>::new();
+        game.set(0, Some(16));
+        game.set(1, Some(10));
+        assert!(game.to_string().starts_with("GA "));
+
+        let parsed = BoxSudoku::<4, 4, 256>::from_str(&game.to_string()).unwrap();
+        assert_eq!(parsed, game);
+        assert_eq!(
+            BoxSudoku::<4, 4, 256>::from_str(&format!("H{}", " ".repeat(255))),
+            Err(SudokuError::InvalidCharacter {
+                position: 0,
+                character: 'H'
+            })
+        );
+    }
+
+    #[test]
+    fn sixteen() {
+        let mut game = BoxSudoku::<4, 4, 256>::from_str(
+            " G     4  D63  E 9       G  8B    D 5 A7 C E2  4  F 8BD 2 14 G  G7     2    EF  FD  E7  G      B 3E C    A 5   2     16 F    5   5 G 28 D7   6CF     FC   4       8B   G 2  A1  9F2   E     7  3  956  D  C     B 3F      92C 7  6 2G      A1   E    59  4  6   ",
+        )
+        .unwrap();
+
+        assert!(game.has_unique_solution());
+        assert!(game.solve().is_ok());
+        assert!(game.solved());
+    }
+
+    #[test]
+    fn alphabets() {
+        let mut game = BoxSudoku::<4, 4, 256>::new();
+        game.solve().unwrap();
+
+        let hexadecimal = game.to_string_with(&Alphabet::hexadecimal());
+        assert!(hexadecimal.contains('0') && !hexadecimal.contains('G'));
+        assert_eq!(
+            BoxSudoku::from_str_with(&hexadecimal, &Alphabet::hexadecimal()),
+            Ok(game)
+        );
+
+        let mut game = BoxSudoku::<5, 5, 625>::new();
+        game.solve().unwrap();
+
+        let letters = game.to_string_with(&Alphabet::letters());
+        assert!(letters.chars().all(|c| ('A'..='Y').contains(&c)));
+        assert_eq!(
+            BoxSudoku::from_str_with(&letters.to_lowercase(), &Alphabet::letters()),
+            Ok(game)
+        );
+        assert_eq!(
+            BoxSudoku::<5, 5, 625>::from_str_with(&letters.replace('A', "Z"), &Alphabet::letters()),
+            Err(SudokuError::InvalidCharacter {
+                position: letters.find('A').unwrap(),
+                character: 'Z'
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn alphabet_too_short() {
+        let mut game = BoxSudoku::<4, 4, 256>::new();
+        game.set(0, Some(16));
+        game.to_string_with(&Alphabet::new("123456789").unwrap());
+    }
+}
@@ -1,4 +1,6 @@
-use crate::sudoku::{Cell, Sudoku};
+use super::parse_cells;
+use crate::error::SudokuError;
+use crate::sudoku::{Cell, GroupKind, Sudoku};
 use std::fmt::Display;
 use std::ops::RangeInclusive;
 use std::str::FromStr;
@@ -21,6 +23,10 @@ impl HyperSudoku {
     }
 
     fn grid(&self, i: usize) -> Vec<Cell> {
+        Self::grid_indices(i).map(|i| self.cells[i]).collect()
+    }
+
+    fn grid_indices(i: usize) -> impl Iterator<Item = usize> {
         let row = i / 3;
         let col = i % 3;
 
@@ -33,9 +39,7 @@ impl HyperSudoku {
 			_ => unreachable!("{i}"),
 		};
 
-        let indices = (offset..(offset + 3)).chain((offset + 9)..(offset + 9 + 3)).chain((offset + 18)..(offset + 18 + 3));
-
-        indices.map(|i| self.cells[i]).collect()
+        (offset..(offset + 3)).chain((offset + 9)..(offset + 9 + 3)).chain((offset + 18)..(offset + 18 + 3))
     }
 }
 
@@ -68,14 +72,14 @@ impl Sudoku for HyperSudoku {
         &mut self.cells
     }
 
-    fn cell_values(&mut self) -> RangeInclusive<u8> {
+    fn cell_values(&self) -> RangeInclusive<u8> {
         1..=9
     }
 
     fn rows(&self) -> Vec<Vec<Cell>> {
         self.cells
             .chunks_exact(9)
-            .map(|c| c.into_iter().copied().collect::<Vec<_>>())
+            .map(|c| c.to_vec())
             .collect()
     }
 
@@ -87,6 +91,23 @@ impl Sudoku for HyperSudoku {
         (0..13).map(|i| self.grid(i)).collect()
     }
 
+    fn group_indices(&self) -> Vec<Vec<usize>> {
+        let rows = (0..9).map(|row| (row * 9..row * 9 + 9).collect());
+        let columns = (0..9).map(|col| (col..9 * 9).step_by(9).collect());
+        let grids = (0..13).map(|i| Self::grid_indices(i).collect());
+
+        rows.chain(columns).chain(grids).collect()
+    }
+
+    fn group_kind(&self, group: usize) -> GroupKind {
+        match group {
+            0..=8 => GroupKind::Row(group),
+            9..=17 => GroupKind::Column(group - 9),
+            18..=26 => GroupKind::Grid(group - 18),
+            _ => GroupKind::Window(group - 27),
+        }
+    }
+
     fn groups_of(&self, i: usize) -> Vec<Vec<Cell>> {
 		let mut v = Vec::with_capacity(4);
 		
@@ -117,7 +138,7 @@ impl Display for HyperSudoku {
                 .cells
                 .iter()
                 .map(|c| match c {
-                    Some(digit) => ('0' as u8 + *digit) as char,
+                    Some(digit) => (b'0' + *digit) as char,
                     None => ' ',
                 })
                 .collect::<String>(),
@@ -126,27 +147,14 @@ impl Display for HyperSudoku {
 }
 
 impl FromStr for HyperSudoku {
-    type Err = &'static str;
+    type Err = SudokuError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let v = s
-            .chars()
-            .into_iter()
-            .filter_map(|c| match c {
-                // 1 to 9 become Some(1..9), ' ' becomes None
-                '1'..='9' | ' ' => Some(c.to_digit(10).map(|d| d as u8)),
-                _ => None,
-            })
-            .collect::<Vec<Cell>>();
-
-        let mut cells = [None; 9 * 9];
-        if v.len() != cells.len() {
-            return Err("invalid length");
-        }
+        let mut game = Self::new();
+        let cells = parse_cells(s, game.cell_values(), game.cells.len())?;
+        game.cells.copy_from_slice(&cells);
 
-        cells.copy_from_slice(&v[..]);
-
-        Ok(Self { cells })
+        Ok(game)
     }
 }
 
@@ -260,4 +268,42 @@ mod tests {
         assert_eq!(game.groups_of(9 * 2 + 3)[3], game.grid(9));
         assert_eq!(game.groups_of(5)[2], game.grid(1));
     }
+
+    #[test]
+    fn group_indices() {
+        let game = HyperSudoku::from_str("       1   2    34    51        65   7 3   8   3          8    58    9  69       ").unwrap();
+
+        let groups = game
+            .group_indices()
+            .into_iter()
+            .map(|group| group.into_iter().map(|i| game.get(i)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(groups, game.groups());
+    }
+
+    #[test]
+    fn solve() {
+        let mut game = HyperSudoku::from_str("       1   2    34    51        65   7 3   8   3          8    58    9  69       ").unwrap();
+
+        assert!(game.solve().is_ok());
+        assert!(game.solved());
+        assert_eq!(
+            game.to_string(),
+            "946832715152697834738451296819726543475319682263548179327985461584163927691274358"
+        );
+    }
+
+    #[test]
+    fn conflicts() {
+        let mut game = HyperSudoku::new();
+        game.set(9 + 1, Some(4));
+        game.set(9 * 3 + 3, Some(4));
+
+        let conflicts = game.conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].cells, (10, 30));
+        assert_eq!(conflicts[0].group, 27);
+        assert_eq!(conflicts[0].kind, GroupKind::Window(0));
+    }
 }
+
@@ -0,0 +1,433 @@
+use super::parse_cells;
+use crate::error::SudokuError;
+use crate::sudoku::{Cell, GroupKind, Sudoku};
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// Jigsaw Sudoku.
+///
+/// Played on a 9x9 grid like standard Sudoku, but the 3x3 subgrids are
+/// replaced by nine irregular regions of nine cells each, which are given
+/// along with the puzzle.
+///
+/// A jigsaw grid is written as a map of its regions followed by its digits,
+/// each of them 81 characters long. Any character but a space can label a
+/// region, as long as every region has its own label.
+///
+/// ```
+/// use std::str::FromStr;
+/// use sudoku_solver::variants::JigsawSudoku;
+/// use sudoku_solver::Sudoku;
+///
+/// let regions = "AAAABBBBBAAABBBCCCADDDBCCCCADDDEEECCFDDEEEGGGFDFEEEHGGFFFFHHHGGFIIIIHHGGFIIIIIHHH";
+/// let digits = " 8    3 1     5     6 74   139 2                 6         3  75 43              ";
+///
+/// let mut game = JigsawSudoku::from_str(&format!("{regions}\n{digits}")).unwrap();
+/// assert_eq!(game.region(9), 0);
+/// assert_eq!(game.region(22), 1);
+///
+/// game.solve().unwrap();
+/// assert!(game.solved());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct JigsawSudoku {
+    cells: [Cell; 9 * 9],
+    regions: [u8; 9 * 9],
+}
+
+impl JigsawSudoku {
+    /// Create an empty grid where cell `i` is part of region `regions[i]`.
+    ///
+    /// The regions are numbered from 0 to 8, and each of them must be made up
+    /// of nine cells that are joined together through their sides.
+    pub fn new(regions: [u8; 9 * 9]) -> Result<Self, SudokuError> {
+        if let Some(&region) = regions.iter().find(|&&r| r >= 9) {
+            return Err(SudokuError::InvalidRegion {
+                region: region as usize,
+            });
+        }
+
+        for region in 0..9 {
+            let cells = (0..81)
+                .filter(|&i| regions[i] == region)
+                .collect::<Vec<_>>();
+            if cells.len() != 9 || !connected(&cells) {
+                return Err(SudokuError::InvalidRegion {
+                    region: region as usize,
+                });
+            }
+        }
+
+        Ok(Self {
+            cells: [None; 9 * 9],
+            regions,
+        })
+    }
+
+    /// Create an empty grid from a map of its regions, with a label for the
+    /// region of every cell.
+    ///
+    /// The regions are numbered in the order their labels first appear.
+    pub fn from_regions(s: &str) -> Result<Self, SudokuError> {
+        let mut labels = Vec::with_capacity(9);
+        let mut regions = Vec::with_capacity(81);
+
+        for (position, character) in s.chars().enumerate() {
+            match character {
+                '\n' | '\r' | '\t' => (),
+                ' ' => {
+                    return Err(SudokuError::InvalidCharacter {
+                        position,
+                        character,
+                    })
+                }
+                _ => {
+                    let region = match labels.iter().position(|&l| l == character) {
+                        Some(region) => region,
+                        None => {
+                            labels.push(character);
+                            labels.len() - 1
+                        }
+                    };
+                    regions.push(region as u8);
+                }
+            }
+        }
+
+        let regions =
+            <[u8; 81]>::try_from(regions).map_err(|regions| SudokuError::InvalidLength {
+                expected: 81,
+                found: regions.len(),
+            })?;
+
+        Self::new(regions)
+    }
+
+    /// Get the region of cell `i`.
+    pub fn region(&self, i: usize) -> usize {
+        self.regions[i] as usize
+    }
+
+    fn region_indices(&self, region: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..81).filter(move |&i| self.region(i) == region)
+    }
+
+    fn grid(&self, region: usize) -> Vec<Cell> {
+        self.region_indices(region).map(|i| self.cells[i]).collect()
+    }
+}
+
+/// Check if `cells` are joined together through their sides.
+pub(super) fn connected(cells: &[usize]) -> bool {
+    let Some(&first) = cells.first() else {
+        return true;
+    };
+
+    let mut reached = vec![first];
+    let mut queue = vec![first];
+    while let Some(i) = queue.pop() {
+        let (row, col) = (i / 9, i % 9);
+        let neighbours = [
+            (row > 0).then(|| i - 9),
+            (row < 8).then(|| i + 9),
+            (col > 0).then(|| i - 1),
+            (col < 8).then(|| i + 1),
+        ];
+
+        for j in neighbours.into_iter().flatten() {
+            if cells.contains(&j) && !reached.contains(&j) {
+                reached.push(j);
+                queue.push(j);
+            }
+        }
+    }
+
+    reached.len() == cells.len()
+}
+
+impl Default for JigsawSudoku {
+    /// Create an empty grid whose regions are the 3x3 subgrids of standard
+    /// Sudoku.
+    fn default() -> Self {
+        Self {
+            cells: [None; 9 * 9],
+            regions: std::array::from_fn(|i| ((i / 27) * 3 + (i % 9) / 3) as u8),
+        }
+    }
+}
+
+impl Sudoku for JigsawSudoku {
+    fn get(&self, i: usize) -> Cell {
+        self.cells[i]
+    }
+
+    fn set(&mut self, i: usize, num: Cell) {
+        if let Some(num) = num {
+            if !self.cell_values().contains(&num) {
+                panic!("{num} is not a valid value for this cell");
+            }
+        }
+
+        self.cells[i] = num;
+    }
+
+    fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut [Cell] {
+        &mut self.cells
+    }
+
+    fn cell_values(&self) -> RangeInclusive<u8> {
+        1..=9
+    }
+
+    fn rows(&self) -> Vec<Vec<Cell>> {
+        self.cells.chunks_exact(9).map(|c| c.to_vec()).collect()
+    }
+
+    fn columns(&self) -> Vec<Vec<Cell>> {
+        (0..9)
+            .map(|i| self.cells.into_iter().skip(i).step_by(9).collect())
+            .collect()
+    }
+
+    fn grids(&self) -> Vec<Vec<Cell>> {
+        (0..9).map(|i| self.grid(i)).collect()
+    }
+
+    fn group_indices(&self) -> Vec<Vec<usize>> {
+        let rows = (0..9).map(|row| (row * 9..row * 9 + 9).collect());
+        let columns = (0..9).map(|col| (col..9 * 9).step_by(9).collect());
+        let regions = (0..9).map(|i| self.region_indices(i).collect());
+
+        rows.chain(columns).chain(regions).collect()
+    }
+
+    fn group_kind(&self, group: usize) -> GroupKind {
+        match group {
+            0..=8 => GroupKind::Row(group),
+            9..=17 => GroupKind::Column(group - 9),
+            _ => GroupKind::Region(group - 18),
+        }
+    }
+
+    fn groups_of(&self, i: usize) -> Vec<Vec<Cell>> {
+        let row = i / 9;
+        let col = i % 9;
+
+        [
+            self.cells.into_iter().skip(row * 9).take(9).collect(),
+            self.cells.into_iter().skip(col).step_by(9).collect(),
+            self.grid(self.region(i)),
+        ]
+        .into()
+    }
+}
+
+impl Display for JigsawSudoku {
+    /// Write the map of the regions, labelled from 1 to 9, and the digits on a
+    /// second line.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let regions = self
+            .regions
+            .iter()
+            .map(|r| (b'1' + r) as char)
+            .collect::<String>();
+        let cells = self
+            .cells
+            .iter()
+            .map(|c| match c {
+                Some(digit) => (b'0' + *digit) as char,
+                None => ' ',
+            })
+            .collect::<String>();
+
+        write!(f, "{regions}\n{cells}")
+    }
+}
+
+impl FromStr for JigsawSudoku {
+    type Err = SudokuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // The map ends after the 81st character that is not a line break.
+        let split = s
+            .char_indices()
+            .filter(|(_, c)| !matches!(c, '\n' | '\r' | '\t'))
+            .nth(81)
+            .map_or(s.len(), |(i, _)| i);
+        let (regions, digits) = s.split_at(split);
+
+        let mut game = Self::from_regions(regions)?;
+        let offset = regions.chars().count();
+        let cells =
+            parse_cells(digits, game.cell_values(), game.cells.len()).map_err(|e| match e {
+                SudokuError::InvalidCharacter {
+                    position,
+                    character,
+                } => SudokuError::InvalidCharacter {
+                    position: position + offset,
+                    character,
+                },
+                e => e,
+            })?;
+        game.cells.copy_from_slice(&cells);
+
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::LogicalSolver;
+
+    const REGIONS: &str = "
+111122222
+111222333
+144423333
+144455533
+644555777
+646555877
+666688877
+699998877
+699999888";
+
+    #[test]
+    fn regions() {
+        let game = JigsawSudoku::from_regions(REGIONS).unwrap();
+        assert_eq!(game.region(0), 0);
+        assert_eq!(game.region(4), 1);
+        assert_eq!(game.region(80), 7);
+        assert_eq!(
+            game.group_indices()[18 + 3],
+            [19, 20, 21, 28, 29, 30, 37, 38, 46]
+        );
+        assert_eq!(game.groups_of(46)[2], game.grid(3));
+
+        let letters = REGIONS.replace('1', "A").replace('9', "z");
+        assert_eq!(JigsawSudoku::from_regions(&letters), Ok(game));
+
+        let boxes = ["111222333", "444555666", "777888999"].map(|band| band.repeat(3));
+        assert_eq!(
+            JigsawSudoku::from_regions(&boxes.concat()),
+            Ok(JigsawSudoku::default())
+        );
+    }
+
+    const PUZZLE: &str =
+        " 8    3 1     5     6 74   139 2                 6         3  75 43              ";
+
+    #[test]
+    fn parse() {
+        let game = JigsawSudoku::from_str(&format!("{REGIONS}\n{PUZZLE}")).unwrap();
+        assert_eq!(game.get(1), Some(8));
+        assert_eq!(game.region(9), 0);
+        assert_eq!(JigsawSudoku::from_str(&game.to_string()), Ok(game));
+        assert_eq!(
+            game.to_string(),
+            format!("{}\n{PUZZLE}", REGIONS.replace('\n', ""))
+        );
+
+        // Positions count from the start of the map.
+        assert_eq!(
+            JigsawSudoku::from_str(&format!("{REGIONS}\n0{}", &PUZZLE[1..])),
+            Err(SudokuError::InvalidCharacter {
+                position: 91,
+                character: '0'
+            })
+        );
+        assert_eq!(
+            JigsawSudoku::from_str(&format!("{REGIONS}\n{}", &PUZZLE[1..])),
+            Err(SudokuError::InvalidLength {
+                expected: 81,
+                found: 80
+            })
+        );
+    }
+
+    #[test]
+    fn solve() {
+        let mut game = JigsawSudoku::from_regions(REGIONS).unwrap();
+        for (i, digit) in PUZZLE.chars().enumerate() {
+            game.set(i, digit.to_digit(10).map(|d| d as u8));
+        }
+
+        let mut solver = LogicalSolver::new(&game).unwrap();
+        solver.solve();
+        assert!(solver.solved());
+
+        assert!(game.has_unique_solution());
+        game.solve().unwrap();
+        assert!(game.solved());
+        assert_eq!(solver.cells(), game.cells());
+        assert_eq!(
+            game.cells().iter().map(|c| c.unwrap()).collect::<Vec<_>>()[..9],
+            [6, 8, 5, 7, 4, 9, 3, 2, 1]
+        );
+    }
+
+    #[test]
+    fn conflicts() {
+        let mut game = JigsawSudoku::from_regions(REGIONS).unwrap();
+        game.set(3, Some(5));
+        game.set(9 * 3, Some(5));
+
+        let conflicts = game.conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].cells, (3, 27));
+        assert_eq!(conflicts[0].kind, GroupKind::Region(0));
+        assert_eq!(conflicts[0].to_string(), "cells 3 and 27 clash in region 0");
+    }
+
+    #[test]
+    fn invalid_regions() {
+        // Region 0 swaps a cell with region 1, which leaves it in two parts.
+        let split =
+            REGIONS
+                .replacen("111122222", "111222222", 1)
+                .replacen("111222333", "111221333", 1);
+        assert_eq!(
+            JigsawSudoku::from_regions(&split),
+            Err(SudokuError::InvalidRegion { region: 0 })
+        );
+
+        // Region 0 takes a cell from region 1.
+        let big = REGIONS.replacen("111122222", "111112222", 1);
+        assert_eq!(
+            JigsawSudoku::from_regions(&big),
+            Err(SudokuError::InvalidRegion { region: 0 })
+        );
+
+        // A tenth label is named, rather than the region it leaves short.
+        let ten = REGIONS.replacen('9', "0", 1);
+        assert_eq!(
+            JigsawSudoku::from_regions(&ten),
+            Err(SudokuError::InvalidRegion { region: 9 })
+        );
+
+        let mut regions = JigsawSudoku::from_regions(REGIONS).unwrap().regions;
+        regions[0] = 9;
+        assert_eq!(
+            JigsawSudoku::new(regions),
+            Err(SudokuError::InvalidRegion { region: 9 })
+        );
+        assert_eq!(
+            JigsawSudoku::from_regions("1112"),
+            Err(SudokuError::InvalidLength {
+                expected: 81,
+                found: 4
+            })
+        );
+        assert_eq!(
+            JigsawSudoku::from_regions(" "),
+            Err(SudokuError::InvalidCharacter {
+                position: 0,
+                character: ' '
+            })
+        );
+    }
+}
@@ -0,0 +1,448 @@
+use super::jigsaw::connected;
+use super::{parse_cells, StandardSudoku};
+use crate::error::SudokuError;
+use crate::sudoku::{Cage, Cell, Sudoku};
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// Killer Sudoku.
+///
+/// Played on a 9x9 grid like standard Sudoku, with the grid split into cages
+/// as well. The digits of a cage must add up to the sum written on it, and
+/// cannot repeat within it. Most puzzles start out without any digits at all,
+/// leaving the sums as the only clues.
+///
+/// A killer grid is written as a map of its cages, a line with the sum of
+/// every cage in the order they first appear on the map, and its digits. The
+/// map is 81 characters long, where any character but a space can label a
+/// cage as long as every cage has its own label, and `.` marks a cell outside
+/// every cage. The digits can be left out when the grid has none.
+///
+/// ```
+/// use std::str::FromStr;
+/// use sudoku_solver::variants::KillerSudoku;
+/// use sudoku_solver::Sudoku;
+///
+/// let cages = "
+/// aabbcddef
+/// gghhcijef
+/// kklmmijno
+/// pplqqrsno
+/// ttvvwrsxx
+/// tuuwwzzAB
+/// CDEFGHHAB
+/// CDEFGIJJK
+/// LLMMNIOOK";
+/// let sums = "10 8 7 7 13 14 15 8 16 8 7 4 14 12 3 14 10 11 8 17 7 17 17 9 6 4 9 8 13 10 9 12 14 7 10 10 5 17 4 11";
+///
+/// let mut game = KillerSudoku::from_str(&format!("{cages}\n{sums}")).unwrap();
+/// assert_eq!(game.cage(1), Some(0));
+/// assert_eq!(game.cages()[0].sum, 10);
+///
+/// game.solve().unwrap();
+/// assert!(game.solved());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KillerSudoku {
+    grid: StandardSudoku,
+    cages: Vec<Cage>,
+}
+
+impl KillerSudoku {
+    /// Create an empty grid with the given cages.
+    ///
+    /// Every cage must be made up of cells that are joined together through
+    /// their sides and aren't part of any other cage, and its sum must be
+    /// reachable with different digits.
+    pub fn new(cages: Vec<Cage>) -> Result<Self, SudokuError> {
+        let mut caged = [false; 9 * 9];
+
+        for (n, cage) in cages.iter().enumerate() {
+            let overlapping = cage
+                .cells
+                .iter()
+                .any(|&i| i >= 81 || std::mem::replace(&mut caged[i], true));
+            if overlapping
+                || cage.cells.is_empty()
+                || !connected(&cage.cells)
+                || !cage.reachable(&[None; 9 * 9], 1..=9)
+            {
+                return Err(SudokuError::InvalidCage { cage: n });
+            }
+        }
+
+        Ok(Self {
+            grid: StandardSudoku::new(),
+            cages,
+        })
+    }
+
+    /// Create an empty grid from a map of its cages, with a label for the
+    /// cage of every cell, and their sums separated by spaces.
+    ///
+    /// The cages are numbered in the order their labels first appear, and
+    /// cells labelled with `.` are left out of every cage.
+    pub fn from_cages(map: &str, sums: &str) -> Result<Self, SudokuError> {
+        let mut labels = Vec::new();
+        let mut cells = Vec::<Vec<usize>>::new();
+        let mut len = 0;
+
+        for (position, character) in map.chars().enumerate() {
+            match character {
+                '\n' | '\r' | '\t' => continue,
+                ' ' => {
+                    return Err(SudokuError::InvalidCharacter {
+                        position,
+                        character,
+                    })
+                }
+                '.' => (),
+                _ => match labels.iter().position(|&l| l == character) {
+                    Some(cage) => cells[cage].push(len),
+                    None => {
+                        labels.push(character);
+                        cells.push(vec![len]);
+                    }
+                },
+            }
+            len += 1;
+        }
+
+        if len != 81 {
+            return Err(SudokuError::InvalidLength {
+                expected: 81,
+                found: len,
+            });
+        }
+
+        let sums = parse_sums(sums)?;
+        if sums.len() != cells.len() {
+            return Err(SudokuError::InvalidLength {
+                expected: cells.len(),
+                found: sums.len(),
+            });
+        }
+
+        let cages = cells
+            .into_iter()
+            .zip(sums)
+            .map(|(cells, sum)| Cage { cells, sum })
+            .collect();
+
+        Self::new(cages)
+    }
+
+    /// Get the cage of cell `i`, if it is part of one.
+    pub fn cage(&self, i: usize) -> Option<usize> {
+        self.cages.iter().position(|cage| cage.cells.contains(&i))
+    }
+}
+
+/// Read the numbers separated by whitespace in `s`.
+fn parse_sums(s: &str) -> Result<Vec<u32>, SudokuError> {
+    let mut sums = Vec::new();
+    let mut sum = None;
+
+    for (position, character) in s.chars().enumerate() {
+        match character.to_digit(10) {
+            Some(digit) => sum = Some(sum.unwrap_or(0) * 10 + digit),
+            None if character.is_whitespace() => sums.extend(sum.take()),
+            None => {
+                return Err(SudokuError::InvalidCharacter {
+                    position,
+                    character,
+                })
+            }
+        }
+    }
+    sums.extend(sum);
+
+    Ok(sums)
+}
+
+/// Move the position of an invalid character `offset` characters further.
+fn offset(error: SudokuError, offset: usize) -> SudokuError {
+    match error {
+        SudokuError::InvalidCharacter {
+            position,
+            character,
+        } => SudokuError::InvalidCharacter {
+            position: position + offset,
+            character,
+        },
+        error => error,
+    }
+}
+
+impl Sudoku for KillerSudoku {
+    fn get(&self, i: usize) -> Cell {
+        self.grid.get(i)
+    }
+
+    fn set(&mut self, i: usize, num: Cell) {
+        self.grid.set(i, num)
+    }
+
+    fn cells(&self) -> &[Cell] {
+        self.grid.cells()
+    }
+
+    fn cells_mut(&mut self) -> &mut [Cell] {
+        self.grid.cells_mut()
+    }
+
+    fn cell_values(&self) -> RangeInclusive<u8> {
+        self.grid.cell_values()
+    }
+
+    fn rows(&self) -> Vec<Vec<Cell>> {
+        self.grid.rows()
+    }
+
+    fn columns(&self) -> Vec<Vec<Cell>> {
+        self.grid.columns()
+    }
+
+    fn grids(&self) -> Vec<Vec<Cell>> {
+        self.grid.grids()
+    }
+
+    fn group_indices(&self) -> Vec<Vec<usize>> {
+        self.grid.group_indices()
+    }
+
+    fn groups_of(&self, i: usize) -> Vec<Vec<Cell>> {
+        self.grid.groups_of(i)
+    }
+
+    fn cages(&self) -> Vec<Cage> {
+        self.cages.clone()
+    }
+}
+
+impl Display for KillerSudoku {
+    /// Write the map of the cages, the sums on a second line and the digits
+    /// on a third.
+    ///
+    /// The cages are labelled with the letters from `a` to `z` and from `A`
+    /// to `Z`, then the digits from 0 to 9, and then the greek letters.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let labels = ('a'..='z')
+            .chain('A'..='Z')
+            .chain('0'..='9')
+            .chain('α'..='ω')
+            .collect::<Vec<_>>();
+        let map = (0..81)
+            .map(|i| self.cage(i).map_or('.', |cage| labels[cage]))
+            .collect::<String>();
+        let sums = self
+            .cages
+            .iter()
+            .map(|cage| cage.sum.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        write!(f, "{map}\n{sums}\n{}", self.grid)
+    }
+}
+
+impl FromStr for KillerSudoku {
+    type Err = SudokuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // The map ends after the 81st character that is not a line break, and
+        // the sums take up the first line after it.
+        let split = s
+            .char_indices()
+            .filter(|(_, c)| !matches!(c, '\n' | '\r' | '\t'))
+            .nth(81)
+            .map_or(s.len(), |(i, _)| i);
+        let (map, rest) = s.split_at(split);
+        let (sums, digits) = rest.split_once('\n').unwrap_or((rest, ""));
+
+        let map_len = map.chars().count();
+        parse_sums(sums).map_err(|e| offset(e, map_len))?;
+        let mut game = Self::from_cages(map, sums)?;
+
+        if !digits.is_empty() {
+            let sums_len = sums.chars().count() + 1;
+            let cells = parse_cells(digits, game.cell_values(), 81)
+                .map_err(|e| offset(e, map_len + sums_len))?;
+            game.cells_mut().copy_from_slice(&cells);
+        }
+
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::LogicalSolver;
+    use crate::sudoku::GroupKind;
+    use crate::transform::Transform;
+
+    const CAGES: &str = "
+aabbcddef
+gghhcijef
+kklmmijno
+pplqqrsno
+ttvvwrsxx
+tuuwwzzAB
+CDEFGHHAB
+CDEFGIJJK
+LLMMNIOOK";
+
+    const SUMS: &str =
+        "10 8 7 7 13 14 15 8 16 8 7 4 14 12 3 14 10 11 8 17 7 17 17 9 6 4 9 8 13 10 9 12 14 7 10 10 5 17 4 11";
+
+    const SOLUTION: &str =
+        "827154396965327148341689752593468271472513689618972435786235914154796823239841567";
+
+    fn game() -> KillerSudoku {
+        KillerSudoku::from_cages(CAGES, SUMS).unwrap()
+    }
+
+    #[test]
+    fn cages() {
+        let game = game();
+        assert_eq!(game.cages().len(), 40);
+        assert_eq!(game.cage(0), Some(0));
+        assert_eq!(game.cage(80), Some(35));
+        assert_eq!(
+            game.cages()[2],
+            Cage {
+                cells: vec![4, 13],
+                sum: 7
+            }
+        );
+
+        let relabelled = CAGES.replace('a', "+").replace('b', "a");
+        assert_eq!(KillerSudoku::from_cages(&relabelled, SUMS), Ok(game));
+
+        let partial = KillerSudoku::from_cages(&CAGES.replace('a', "."), &SUMS[3..]).unwrap();
+        assert_eq!(partial.cage(0), None);
+        assert_eq!(partial.cages().len(), 39);
+    }
+
+    #[test]
+    fn parse() {
+        let game = game();
+        assert_eq!(
+            KillerSudoku::from_str(&format!("{CAGES}\n{SUMS}")),
+            Ok(game.clone())
+        );
+        assert_eq!(KillerSudoku::from_str(&game.to_string()), Ok(game.clone()));
+
+        let mut given = game.clone();
+        given.set(0, Some(8));
+        assert_eq!(KillerSudoku::from_str(&given.to_string()), Ok(given));
+        assert_eq!(
+            KillerSudoku::from_str(&format!("{CAGES}\n{SUMS}\n8")),
+            Err(SudokuError::InvalidLength {
+                expected: 81,
+                found: 1
+            })
+        );
+
+        assert_eq!(
+            KillerSudoku::from_str(&format!("{CAGES}\n{}", SUMS.replace("13", "1x"))),
+            Err(SudokuError::InvalidCharacter {
+                position: CAGES.len() + 11,
+                character: 'x'
+            })
+        );
+        assert_eq!(
+            KillerSudoku::from_cages(CAGES, &SUMS[3..]),
+            Err(SudokuError::InvalidLength {
+                expected: 40,
+                found: 39
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_cages() {
+        // Two cages with the same label that don't touch.
+        assert_eq!(
+            KillerSudoku::from_cages(&CAGES.replace('b', "a"), &SUMS[2..]),
+            Err(SudokuError::InvalidCage { cage: 0 })
+        );
+        // Two cells can't add up to more than 17.
+        assert_eq!(
+            KillerSudoku::from_cages(CAGES, &SUMS.replacen("8", "18", 1)),
+            Err(SudokuError::InvalidCage { cage: 1 })
+        );
+
+        let overlapping = vec![
+            Cage {
+                cells: vec![0, 1],
+                sum: 3,
+            },
+            Cage {
+                cells: vec![1, 2],
+                sum: 3,
+            },
+        ];
+        assert_eq!(
+            KillerSudoku::new(overlapping),
+            Err(SudokuError::InvalidCage { cage: 1 })
+        );
+        assert_eq!(
+            KillerSudoku::new(vec![Cage {
+                cells: Vec::new(),
+                sum: 0
+            }]),
+            Err(SudokuError::InvalidCage { cage: 0 })
+        );
+    }
+
+    #[test]
+    fn solve() {
+        let mut game = game();
+        assert!(game.has_unique_solution());
+
+        // Unique rectangles don't hold once the sums tell their digits apart.
+        assert!(!LogicalSolver::new(&game).unwrap().unique());
+
+        game.solve().unwrap();
+        assert!(game.solved());
+        assert_eq!(game.grid.to_string(), SOLUTION);
+    }
+
+    #[test]
+    fn rules() {
+        let mut game = game();
+        assert!(game.legal());
+
+        // The cages are reported after the row and subgrid the cells share.
+        game.set(0, Some(5));
+        game.set(1, Some(5));
+        assert!(!game.legal());
+        let conflicts = game.conflicts();
+        assert_eq!(conflicts.len(), 3);
+        assert_eq!(conflicts[2].cells, (0, 1));
+        assert_eq!(conflicts[2].group, 27);
+        assert_eq!(conflicts[2].kind, GroupKind::Cage(0));
+        assert_eq!(conflicts[2].to_string(), "cells 0 and 1 clash in cage 0");
+
+        // The second cage adds up to 8, which 9 already overshoots.
+        game.set(0, None);
+        game.set(1, None);
+        game.set(2, Some(9));
+        assert!(game.conflicts().is_empty());
+        assert!(!game.legal());
+        assert_eq!(game.solve(), Err(SudokuError::Unsolvable));
+
+        // A grid that follows the rules of every group but not the sums.
+        let solution = StandardSudoku::from_str(SOLUTION).unwrap();
+        game.cells_mut()
+            .copy_from_slice(solution.relabel(&[9, 8, 7, 6, 5, 4, 3, 2, 1]).cells());
+        assert!(game.conflicts().is_empty());
+        assert!(!game.solved());
+
+        game.cells_mut().copy_from_slice(solution.cells());
+        assert!(game.solved());
+    }
+}
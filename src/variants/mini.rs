@@ -1,139 +1,17 @@
-use crate::sudoku::{Cell, Sudoku};
-use std::fmt::Display;
-use std::str::FromStr;
+use super::BoxSudoku;
 
 /// Mini Sudoku.
 ///
 /// A 6x6 grid with 3x2 sub-grids, which is otherwise played exactly the same
 /// as regular Sudoku with the digits from 1 to 6.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub struct MiniSudoku {
-    cells: [Cell; 6 * 6],
-}
-
-impl MiniSudoku {
-    /// Create a new mini Sudoku grid.
-    pub fn new() -> Self {
-        Self {
-            cells: [None; 6 * 6],
-        }
-    }
-
-    fn row(&self, i: usize) -> Vec<Cell> {
-        self.cells.into_iter().skip(i * 6).take(6).collect()
-    }
-
-    fn column(&self, i: usize) -> Vec<Cell> {
-        self.cells.into_iter().skip(i).step_by(6).collect()
-    }
-
-    fn grid(&self, i: usize) -> Vec<Cell> {
-        let row = i / 2;
-        let col = i % 2;
-
-        self.cells
-            .chunks_exact(3)
-            .skip(row * 4)
-            .skip(col)
-            .step_by(2)
-            .take(2)
-            .flatten()
-            .copied()
-            .collect()
-    }
-}
-
-impl Sudoku for MiniSudoku {
-    fn get(&self, i: usize) -> Cell {
-        self.cells[i]
-    }
-
-    fn set(&mut self, i: usize, num: Cell) {
-        if let Some(num) = num {
-            if !self.cell_values().contains(&num) {
-                panic!("{num} is not a valid value for this cell");
-            }
-        }
-
-        self.cells[i] = num;
-    }
-
-    fn cells(&self) -> &[Cell] {
-        &self.cells
-    }
-
-    fn cells_mut(&mut self) -> &mut [Cell] {
-        &mut self.cells
-    }
-
-    fn cell_values(&mut self) -> std::ops::RangeInclusive<u8> {
-        1..=6
-    }
-
-    fn rows(&self) -> Vec<Vec<Cell>> {
-        (0..6).map(|i| self.row(i)).collect()
-    }
-
-    fn columns(&self) -> Vec<Vec<Cell>> {
-        (0..6).map(|i| self.column(i)).collect()
-    }
-
-    fn grids(&self) -> Vec<Vec<Cell>> {
-        (0..6).map(|i| self.grid(i)).collect()
-    }
-
-    fn groups_of(&self, i: usize) -> Vec<Vec<Cell>> {
-        let row = i / 6;
-        let col = i % 6;
-        let group = (row / 2) * 2 + (col / 3);
-
-        [self.row(row), self.column(col), self.grid(group)].into()
-    }
-}
-
-impl Display for MiniSudoku {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(
-            &self
-                .cells
-                .into_iter()
-                .map(|c| match c {
-                    Some(num) => ('0' as u8 + num) as char,
-                    None => ' ',
-                })
-                .collect::<String>(),
-        )
-    }
-}
-
-impl FromStr for MiniSudoku {
-    type Err = &'static str;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let v = s
-            .chars()
-            .into_iter()
-            .filter_map(|c| match c {
-                '1'..='6' | ' ' => Some(c.to_digit(10).map(|n| n as u8)),
-                _ => None,
-            })
-            .collect::<Vec<_>>();
-
-        let mut cells = [None; 6 * 6];
-
-        if v.len() != cells.len() {
-            return Err("invalid length");
-        }
-
-        cells.copy_from_slice(&v[..]);
-
-        Ok(Self { cells })
-    }
-}
+pub type MiniSudoku = BoxSudoku<3, 2, 36>;
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::SudokuError;
+    use crate::sudoku::Sudoku;
+    use std::str::FromStr;
 
     #[test]
     fn groups() {
@@ -162,4 +40,36 @@ mod tests {
         assert_eq!(groups_of[1], game.column(3));
         assert_eq!(groups_of[2], game.grid(3));
     }
+
+    #[test]
+    fn group_indices() {
+        let game = MiniSudoku::from_str("  5 642645 1  3 4  561 3 4 3 66    2").unwrap();
+
+        let groups = game
+            .group_indices()
+            .into_iter()
+            .map(|group| group.into_iter().map(|i| game.get(i)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(groups, game.groups());
+    }
+
+    #[test]
+    fn solve() {
+        let mut game = MiniSudoku::from_str("1 3 5  5 1 32 1 6  6 2 13 2 4  4 3 2").unwrap();
+
+        assert!(game.solve().is_ok());
+        assert!(game.solved());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            MiniSudoku::from_str("1 3 5  5 1 32 1 6  6 2 13 2 4  4 3 7"),
+            Err(SudokuError::InvalidCharacter {
+                position: 35,
+                character: '7'
+            })
+        );
+    }
 }
+
@@ -1,9 +1,72 @@
 //! Implementations of different variants of Sudoku.
 
+mod alphabet;
+mod diagonal;
+mod generic;
+mod jigsaw;
+mod killer;
 mod mini;
 mod standard;
 mod hyper;
 
+pub use alphabet::*;
+pub use diagonal::*;
+pub use generic::*;
+pub use jigsaw::*;
+pub use killer::*;
 pub use mini::*;
 pub use standard::*;
 pub use hyper::*;
+
+use crate::error::SudokuError;
+use crate::sudoku::Cell;
+use std::ops::RangeInclusive;
+
+/// Read `len` cells from a string written with the
+/// [alphanumeric](Alphabet::alphanumeric) alphabet.
+pub(crate) fn parse_cells(
+    s: &str,
+    values: RangeInclusive<u8>,
+    len: usize,
+) -> Result<Vec<Cell>, SudokuError> {
+    parse_cells_with(s, &Alphabet::alphanumeric(), values, len)
+}
+
+/// Read `len` cells from a string.
+///
+/// Symbols of `alphabet` for digits in `values` become set cells and spaces
+/// become empty cells. Line breaks and tabs are skipped, so that grids can be
+/// written one row per line.
+pub(crate) fn parse_cells_with(
+    s: &str,
+    alphabet: &Alphabet,
+    values: RangeInclusive<u8>,
+    len: usize,
+) -> Result<Vec<Cell>, SudokuError> {
+    let mut cells = Vec::with_capacity(len);
+
+    for (position, character) in s.chars().enumerate() {
+        match character {
+            ' ' => cells.push(None),
+            '\n' | '\r' | '\t' => (),
+            _ => match alphabet.digit(character) {
+                Some(digit) if values.contains(&digit) => cells.push(Some(digit)),
+                _ => {
+                    return Err(SudokuError::InvalidCharacter {
+                        position,
+                        character,
+                    })
+                }
+            },
+        }
+    }
+
+    if cells.len() != len {
+        return Err(SudokuError::InvalidLength {
+            expected: len,
+            found: cells.len(),
+        });
+    }
+
+    Ok(cells)
+}